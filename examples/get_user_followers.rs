@@ -1,3 +1,5 @@
+#![allow(clippy::single_component_path_imports)]
+
 use tiktok_rust::{
     client,
     error::TikTokApiError,
@@ -1,3 +1,5 @@
+#![allow(clippy::single_component_path_imports)]
+
 use tiktok_rust::videos;
 use tiktok_rust::{error::TikTokApiError, videos::VideoField};
 use tokio;
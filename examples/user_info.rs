@@ -1,4 +1,9 @@
-use tiktok_rust::{error::TikTokApiError, user};
+#![allow(clippy::single_component_path_imports)]
+
+use tiktok_rust::{
+    error::TikTokApiError,
+    user::{self, UserField},
+};
 use tokio;
 
 #[tokio::main]
@@ -10,16 +15,16 @@ async fn main() -> Result<(), TikTokApiError> {
     let token = "<you user token after oauth auth_example.rs>";
 
     let fields = vec![
-        "open_id",
-        "union_id",
-        "avatar_url",
-        "display_name",
-        "bio_description",
-        "is_verified",
-        "follower_count",
-        "following_count",
-        "likes_count",
-        "video_count",
+        UserField::OpenId,
+        UserField::UnionId,
+        UserField::AvatarUrl,
+        UserField::DisplayName,
+        UserField::BioDescription,
+        UserField::IsVerified,
+        UserField::FollowerCount,
+        UserField::FollowingCount,
+        UserField::LikesCount,
+        UserField::VideoCount,
     ];
 
     // Get user info
@@ -0,0 +1,607 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use reqwest::header::{AUTHORIZATION, RETRY_AFTER};
+use reqwest::{Client, Proxy, Request, RequestBuilder, StatusCode};
+
+use super::{RateLimiter, RetryPolicy};
+use crate::auth::{AuthService, OAuthConfig};
+use crate::error::TikTokApiError;
+use crate::{client, creator, direct_post, research, user, videos};
+
+pub const DEFAULT_BASE_URL: &str = "https://open.tiktokapis.com";
+
+/// Entry point to the TikTok API.
+///
+/// Owns a single pooled `reqwest::Client` that is shared by every service it hands out, so all
+/// requests reuse the same connections instead of opening a new one per call. Cloning a
+/// `TikTokClient` is cheap and keeps sharing the same pool.
+#[derive(Clone, Debug)]
+pub struct TikTokClient {
+    http: Client,
+    base_url: String,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl TikTokClient {
+    /// Creates a new client with the default configuration.
+    pub fn new() -> Self {
+        Self {
+            http: Client::new(),
+            base_url: String::from(DEFAULT_BASE_URL),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
+        }
+    }
+
+    /// Returns a builder to configure the base URL, timeouts, proxy and user agent.
+    pub fn builder() -> TikTokClientBuilder {
+        TikTokClientBuilder::default()
+    }
+
+    /// Sets a custom base URL for the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - A string slice that holds the custom base URL.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Sets the retry policy applied to every request.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry_policy` - The policy deciding which failures are retried and how long to wait.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the retry policy applied to every request.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Throttles every request with the given rate limiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate_limiter` - The limiter shared by this client and all of its clones.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Returns the rate limiter throttling requests, if any.
+    pub fn rate_limiter(&self) -> Option<&Arc<RateLimiter>> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Returns the base URL requests are sent to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the underlying HTTP client.
+    pub fn http(&self) -> &Client {
+        &self.http
+    }
+
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Sends a request and decodes its response, retrying according to the retry policy.
+    ///
+    /// Every attempt first takes a request from the rate limiter, if one is configured.
+    /// Requests whose body cannot be cloned (e.g. streamed uploads) are sent only once.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to send.
+    /// * `decode` - Turns the HTTP status and response body into the result.
+    pub(crate) async fn execute<T, F>(
+        &self,
+        request: RequestBuilder,
+        decode: F,
+    ) -> Result<T, TikTokApiError>
+    where
+        F: Fn(StatusCode, &str) -> Result<T, TikTokApiError>,
+    {
+        self.execute_request(request, decode, true).await
+    }
+
+    /// Same as `execute` for requests that must not be sent twice, such as post inits, which
+    /// would create a duplicate post, or single-use authorization code exchanges.
+    ///
+    /// Only `ConnectFailed` errors are retried, as the request never left the client. Any
+    /// other failure, including timeouts and 5xx responses, is returned right away since TikTok
+    /// may already have processed the request.
+    pub(crate) async fn execute_once<T, F>(
+        &self,
+        request: RequestBuilder,
+        decode: F,
+    ) -> Result<T, TikTokApiError>
+    where
+        F: Fn(StatusCode, &str) -> Result<T, TikTokApiError>,
+    {
+        self.execute_request(request, decode, false).await
+    }
+
+    async fn execute_request<T, F>(
+        &self,
+        request: RequestBuilder,
+        decode: F,
+        idempotent: bool,
+    ) -> Result<T, TikTokApiError>
+    where
+        F: Fn(StatusCode, &str) -> Result<T, TikTokApiError>,
+    {
+        let mut request = request
+            .build()
+            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+        let started = Instant::now();
+        let mut attempt = 1;
+
+        loop {
+            let retry_request = request.try_clone();
+            let (result, retry_after) = self.send_once(request, &decode).await;
+
+            let error = match result {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            let Some(retry_request) = retry_request else {
+                return Err(error);
+            };
+            if !idempotent && !matches!(error, TikTokApiError::ConnectFailed(_)) {
+                return Err(error);
+            }
+            let Some(delay) =
+                self.retry_policy
+                    .next_delay(&error, attempt, started.elapsed(), retry_after)
+            else {
+                return Err(error);
+            };
+
+            tokio::time::sleep(delay).await;
+            request = retry_request;
+            attempt += 1;
+        }
+    }
+
+    /// Same as `execute`, but calls `build` for every attempt instead of cloning the request,
+    /// so requests with streaming bodies can be retried.
+    pub(crate) async fn execute_with<T, B, F>(
+        &self,
+        build: B,
+        decode: F,
+    ) -> Result<T, TikTokApiError>
+    where
+        B: Fn() -> RequestBuilder,
+        F: Fn(StatusCode, &str) -> Result<T, TikTokApiError>,
+    {
+        let started = Instant::now();
+        let mut attempt = 1;
+
+        loop {
+            let request = build()
+                .build()
+                .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            let (result, retry_after) = self.send_once(request, &decode).await;
+
+            let error = match result {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            let Some(delay) =
+                self.retry_policy
+                    .next_delay(&error, attempt, started.elapsed(), retry_after)
+            else {
+                return Err(error);
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send_once<T, F>(
+        &self,
+        request: Request,
+        decode: &F,
+    ) -> (Result<T, TikTokApiError>, Option<Duration>)
+    where
+        F: Fn(StatusCode, &str) -> Result<T, TikTokApiError>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            let token = request
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim_start_matches("Bearer ").to_string())
+                .unwrap_or_default();
+            if let Err(e) = rate_limiter.acquire(request.url().path(), &token).await {
+                return (Err(e), None);
+            }
+        }
+
+        let request_method = request.method().clone();
+        let request_path = request.url().path().to_string();
+        let response = match self.http.execute(request).await {
+            Ok(response) => response,
+            Err(e) if e.is_connect() => {
+                return (Err(TikTokApiError::ConnectFailed(e.to_string())), None)
+            }
+            Err(e) => return (Err(TikTokApiError::RequestFailed(e.to_string())), None),
+        };
+
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+
+        let result = match response.text().await {
+            Ok(body) => decode(status, &body).map_err(|error| {
+                error.with_request(status.as_u16(), request_method.as_str(), &request_path)
+            }),
+            Err(e) => Err(TikTokApiError::ResponseReadFailed(e.to_string())),
+        };
+        (result, retry_after)
+    }
+
+    /// Returns an `AuthService` for the given OAuth configuration.
+    pub fn auth(&self, config: OAuthConfig) -> AuthService {
+        AuthService::from_client(self.clone(), config)
+    }
+
+    /// Returns a `client::Service` using the given client credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_key` - A string slice that holds the client key.
+    /// * `client_secret` - A string slice that holds the client secret.
+    pub fn client_credentials(&self, client_key: &str, client_secret: &str) -> client::Service {
+        client::Service::from_client(self.clone(), client_key, client_secret)
+    }
+
+    /// Returns a `creator::Service` sharing this client.
+    pub fn creator(&self) -> creator::Service {
+        creator::Service::from_client(self.clone())
+    }
+
+    /// Returns a `direct_post::Service` sharing this client.
+    pub fn direct_post(&self) -> direct_post::Service {
+        direct_post::Service::from_client(self.clone())
+    }
+
+    /// Returns a `research::Service` sharing this client.
+    pub fn research(&self) -> research::Service {
+        research::Service::from_client(self.clone())
+    }
+
+    /// Returns a `user::Service` sharing this client.
+    pub fn user(&self) -> user::Service {
+        user::Service::from_client(self.clone())
+    }
+
+    /// Returns a `videos::Service` sharing this client.
+    pub fn videos(&self) -> videos::Service {
+        videos::Service::from_client(self.clone())
+    }
+}
+
+impl Default for TikTokClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder for `TikTokClient`.
+#[derive(Debug, Default)]
+pub struct TikTokClientBuilder {
+    base_url: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    user_agent: Option<String>,
+    http: Option<Client>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl TikTokClientBuilder {
+    /// Sets a custom base URL.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sets the total timeout applied to every request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for establishing a connection.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Routes every request through the given proxy URL.
+    pub fn proxy(mut self, proxy_url: &str) -> Self {
+        self.proxy = Some(proxy_url.into());
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Uses an already configured `reqwest::Client` instead of building one.
+    ///
+    /// Timeout, proxy and user agent settings are ignored when this is set.
+    pub fn http_client(mut self, http: Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Sets the retry policy applied to every request.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Throttles every request with the given rate limiter.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(Arc::new(rate_limiter));
+        self
+    }
+
+    /// Builds the `TikTokClient`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TikTokClient, TikTokApiError>` - The client or an error if the HTTP client could not be built.
+    pub fn build(self) -> Result<TikTokClient, TikTokApiError> {
+        let http = match self.http {
+            Some(http) => http,
+            None => {
+                let mut builder = Client::builder();
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(proxy_url) = &self.proxy {
+                    let proxy = Proxy::all(proxy_url)
+                        .map_err(|e| TikTokApiError::ClientBuildFailed(e.to_string()))?;
+                    builder = builder.proxy(proxy);
+                }
+                if let Some(user_agent) = &self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                builder
+                    .build()
+                    .map_err(|e| TikTokApiError::ClientBuildFailed(e.to_string()))?
+            }
+        };
+
+        Ok(TikTokClient {
+            http,
+            base_url: self
+                .base_url
+                .unwrap_or_else(|| String::from(DEFAULT_BASE_URL)),
+            retry_policy: self.retry_policy.unwrap_or_default(),
+            rate_limiter: self.rate_limiter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::direct_post::{PostInfo, PrivacyLevel, Source, SourceInfoBuilder, VideoInitRequest};
+    use crate::videos::VideoField;
+
+    fn rate_limited() -> ResponseTemplate {
+        ResponseTemplate::new(429)
+            .insert_header("Retry-After", "0")
+            .set_body_json(json!({
+                "data": {},
+                "error": {"code": "rate_limit_exceeded", "message": "", "log_id": "log"}
+            }))
+    }
+
+    #[test]
+    fn test_builder_defaults_to_tiktok_base_url() {
+        let client = TikTokClient::builder().build().unwrap();
+        assert_eq!(client.base_url(), DEFAULT_BASE_URL);
+    }
+
+    #[test]
+    fn test_builder_with_custom_settings() {
+        let client = TikTokClient::builder()
+            .base_url("http://localhost:8080")
+            .timeout(Duration::from_secs(5))
+            .user_agent("tiktok_rust-test")
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "http://localhost:8080");
+        assert_eq!(
+            client.url("/v2/video/list/"),
+            "http://localhost:8080/v2/video/list/"
+        );
+    }
+
+    #[test]
+    fn test_invalid_proxy_fails_to_build() {
+        let result = TikTokClient::builder().proxy("not a url").build();
+        assert!(matches!(result, Err(TikTokApiError::ClientBuildFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_execute_retries_rate_limited_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/video/list/"))
+            .respond_with(rate_limited())
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/video/list/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"videos": [{"id": "1"}], "cursor": 1, "has_more": false},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .mount(&server)
+            .await;
+
+        let retries = Arc::new(AtomicU32::new(0));
+        let counter = retries.clone();
+        let client = TikTokClient::new()
+            .with_base_url(&server.uri())
+            .with_retry_policy(RetryPolicy::new().on_retry(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }));
+
+        let data = client
+            .videos()
+            .list_videos("token", None, None, vec![VideoField::Id])
+            .await
+            .unwrap();
+        assert_eq!(data.videos[0].id, "1");
+        assert_eq!(retries.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_gives_up_after_max_attempts() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/video/list/"))
+            .respond_with(rate_limited())
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = TikTokClient::new()
+            .with_base_url(&server.uri())
+            .with_retry_policy(RetryPolicy::new().with_max_attempts(2));
+
+        let result = client
+            .videos()
+            .list_videos("token", None, None, vec![VideoField::Id])
+            .await;
+        let error = result.unwrap_err();
+        assert!(matches!(error, TikTokApiError::RateLimitExceeded(_)));
+        let details = error.details().unwrap();
+        assert_eq!(details.status, 429);
+        assert_eq!(details.method, "POST");
+        assert_eq!(details.path, "/v2/video/list/");
+    }
+
+    fn video_init_request() -> VideoInitRequest {
+        VideoInitRequest {
+            post_info: PostInfo {
+                title: "title".to_string(),
+                privacy_level: PrivacyLevel::SelfOnly,
+                disable_duet: false,
+                disable_comment: false,
+                disable_stitch: false,
+                video_cover_timestamp_ms: 0,
+            },
+            source_info: SourceInfoBuilder::default()
+                .source(Source::PullFromUrl)
+                .video_url("https://example.com/video.mp4".to_string())
+                .build()
+                .unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_init_is_sent_once_on_server_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/video/init/"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = TikTokClient::new()
+            .with_base_url(&server.uri())
+            .with_retry_policy(RetryPolicy::new().with_base_delay(Duration::ZERO))
+            .direct_post()
+            .post_video("token", video_init_request())
+            .await;
+        assert!(
+            matches!(result, Err(TikTokApiError::EmptyResponse(details)) if details.status == 502)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_non_json_response_records_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/video/init/"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("<html>Unavailable</html>"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let error = TikTokClient::new()
+            .with_base_url(&server.uri())
+            .direct_post()
+            .post_video("token", video_init_request())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, TikTokApiError::NonJsonResponse(_)));
+        let details = error.details().unwrap();
+        assert_eq!(details.status, 503);
+        assert_eq!(details.method, "POST");
+        assert_eq!(details.path, "/v2/post/publish/video/init/");
+        assert!(error
+            .to_string()
+            .contains("for POST /v2/post/publish/video/init/: <html>Unavailable</html>"));
+        assert_eq!(error.log_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_post_init_is_retried_when_connecting_fails() {
+        let retries = Arc::new(AtomicU32::new(0));
+        let counter = retries.clone();
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::ZERO)
+            .on_retry(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+
+        // Nothing listens on port 1, so the connection is refused before anything is sent.
+        let result = TikTokClient::new()
+            .with_base_url("http://127.0.0.1:1")
+            .with_retry_policy(policy)
+            .direct_post()
+            .post_video("token", video_init_request())
+            .await;
+        assert!(matches!(result, Err(TikTokApiError::ConnectFailed(_))));
+        assert_eq!(retries.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,260 @@
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::client::TokenErrorResponse;
+use crate::error::{ApiErrorDetails, ErrorResponse, TikTokApiError};
+
+/// The number of characters of a response body kept in errors.
+pub(crate) const MAX_BODY_EXCERPT: usize = 512;
+
+/// Decodes a response wrapped in TikTok's `{"data": ..., "error": ...}` envelope.
+///
+/// The envelope error is checked before `R` is parsed, so error responses whose `data` does not
+/// match `R` still surface TikTok's error.
+///
+/// # Arguments
+///
+/// * `status` - The HTTP status of the response.
+/// * `body` - The response body.
+/// * `data` - Extracts the result from the parsed response.
+pub(crate) fn decode_envelope<R, T>(
+    status: StatusCode,
+    body: &str,
+    data: impl FnOnce(R) -> T,
+) -> Result<T, TikTokApiError>
+where
+    R: DeserializeOwned,
+{
+    let value = parse_body(status, body)?;
+    if let Some(error) = response_error(status, &value, body) {
+        return Err(error);
+    }
+    let response: R = serde_json::from_value(value).map_err(|e| parse_failed(e, body))?;
+    Ok(data(response))
+}
+
+/// Decodes a response whose body is `T` on success, as returned by the OAuth endpoints.
+///
+/// # Arguments
+///
+/// * `status` - The HTTP status of the response.
+/// * `body` - The response body.
+pub(crate) fn decode_json<T>(status: StatusCode, body: &str) -> Result<T, TikTokApiError>
+where
+    T: DeserializeOwned,
+{
+    let value = parse_body(status, body)?;
+    if let Some(error) = response_error(status, &value, body) {
+        return Err(error);
+    }
+    serde_json::from_value(value).map_err(|e| parse_failed(e, body))
+}
+
+/// Decodes a response that carries no data on success, such as a token revocation. An empty
+/// body is accepted when the status is successful.
+///
+/// # Arguments
+///
+/// * `status` - The HTTP status of the response.
+/// * `body` - The response body.
+pub(crate) fn decode_empty(status: StatusCode, body: &str) -> Result<(), TikTokApiError> {
+    if status.is_success() && body.trim().is_empty() {
+        return Ok(());
+    }
+    let value = parse_body(status, body)?;
+    match response_error(status, &value, body) {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Returns the error described by a failed response, whatever its body.
+///
+/// # Arguments
+///
+/// * `status` - The HTTP status of the response.
+/// * `body` - The response body.
+pub(crate) fn decode_failure(status: StatusCode, body: &str) -> TikTokApiError {
+    match parse_body(status, body) {
+        Ok(value) => response_error(status, &value, body)
+            .unwrap_or_else(|| unexpected_response(status, body)),
+        Err(error) => error,
+    }
+}
+
+fn parse_body(status: StatusCode, body: &str) -> Result<Value, TikTokApiError> {
+    if body.trim().is_empty() {
+        return Err(TikTokApiError::EmptyResponse(Box::new(ApiErrorDetails {
+            status: status.as_u16(),
+            ..ApiErrorDetails::default()
+        })));
+    }
+    serde_json::from_str(body).map_err(|_| {
+        TikTokApiError::NonJsonResponse(Box::new(ApiErrorDetails {
+            status: status.as_u16(),
+            message: excerpt(body),
+            ..ApiErrorDetails::default()
+        }))
+    })
+}
+
+/// Returns the error in `value`, which may be an Open API envelope error, an OAuth error or a
+/// bare error object. A failed status without a recognizable error is also an error.
+fn response_error(status: StatusCode, value: &Value, body: &str) -> Option<TikTokApiError> {
+    match value.get("error") {
+        Some(Value::Object(_)) => {
+            let error: ErrorResponse = match serde_json::from_value(value["error"].clone()) {
+                Ok(error) => error,
+                Err(e) => return Some(parse_failed(e, body)),
+            };
+            if error.code != "ok" {
+                return Some(TikTokApiError::from(error));
+            }
+        }
+        Some(Value::String(_)) => {
+            if let Ok(error) = serde_json::from_value::<TokenErrorResponse>(value.clone()) {
+                return Some(TikTokApiError::ApiError(Box::new(ApiErrorDetails {
+                    code: error.error,
+                    message: error.error_description,
+                    log_id: error.log_id,
+                    ..ApiErrorDetails::default()
+                })));
+            }
+        }
+        _ => {
+            if let Ok(error) = serde_json::from_value::<ErrorResponse>(value.clone()) {
+                if error.code != "ok" {
+                    return Some(TikTokApiError::from(error));
+                }
+            }
+        }
+    }
+
+    (!status.is_success()).then(|| unexpected_response(status, body))
+}
+
+fn unexpected_response(status: StatusCode, body: &str) -> TikTokApiError {
+    TikTokApiError::Unknown(Box::new(ApiErrorDetails {
+        status: status.as_u16(),
+        message: excerpt(body),
+        ..ApiErrorDetails::default()
+    }))
+}
+
+fn parse_failed(error: serde_json::Error, body: &str) -> TikTokApiError {
+    TikTokApiError::ParseFailed(Box::new(ApiErrorDetails {
+        message: format!("{} in body {}", error, excerpt(body)),
+        ..ApiErrorDetails::default()
+    }))
+}
+
+/// Returns the start of `body`, cut after `MAX_BODY_EXCERPT` characters.
+fn excerpt(body: &str) -> String {
+    let body = body.trim();
+    match body.char_indices().nth(MAX_BODY_EXCERPT) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Response {
+        data: Data,
+    }
+
+    #[derive(Deserialize)]
+    struct Data {
+        id: String,
+    }
+
+    fn decode(status: u16, body: &str) -> Result<String, TikTokApiError> {
+        decode_envelope(
+            StatusCode::from_u16(status).unwrap(),
+            body,
+            |r: Response| r.data.id,
+        )
+    }
+
+    #[test]
+    fn test_decode_envelope() {
+        let ok = r#"{"data":{"id":"1"},"error":{"code":"ok","message":"","log_id":"log"}}"#;
+        assert_eq!(decode(200, ok).unwrap(), "1");
+
+        // The data of an error response rarely matches the success shape.
+        let error = r#"{"data":{},"error":{"code":"invalid_params","message":"bad max_count","log_id":"log"}}"#;
+        match decode(400, error) {
+            Err(TikTokApiError::InvalidParams(details)) => {
+                assert_eq!(details.message, "bad max_count")
+            }
+            other => panic!("expected InvalidParams, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_decode_oauth_error() {
+        let body = r#"{"error":"invalid_grant","error_description":"Authorization code is expired.","log_id":"log"}"#;
+        let result: Result<Value, _> = decode_json(StatusCode::BAD_REQUEST, body);
+        match result {
+            Err(TikTokApiError::ApiError(details)) => {
+                assert_eq!(details.code, "invalid_grant");
+                assert_eq!(details.message, "Authorization code is expired.");
+            }
+            other => panic!("expected ApiError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_empty_and_non_json_bodies() {
+        assert!(matches!(
+            decode(204, "  "),
+            Err(TikTokApiError::EmptyResponse(details)) if details.status == 204
+        ));
+
+        let html = format!(
+            "<html><body>{}</body></html>",
+            "502 Bad Gateway ".repeat(100)
+        );
+        match decode(502, &html) {
+            Err(TikTokApiError::NonJsonResponse(details)) => {
+                assert_eq!(details.status, 502);
+                assert!(details.message.starts_with("<html><body>502 Bad Gateway"));
+                assert_eq!(details.message.chars().count(), MAX_BODY_EXCERPT + 3);
+            }
+            other => panic!("expected NonJsonResponse, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_empty() {
+        assert!(decode_empty(StatusCode::OK, "").is_ok());
+        assert!(decode_empty(StatusCode::OK, "{}").is_ok());
+        assert!(matches!(
+            decode_empty(
+                StatusCode::OK,
+                r#"{"error":"invalid_request","error_description":"Bad token","log_id":"log"}"#
+            ),
+            Err(TikTokApiError::ApiError(_))
+        ));
+        assert!(matches!(
+            decode_empty(StatusCode::INTERNAL_SERVER_ERROR, ""),
+            Err(TikTokApiError::EmptyResponse(details)) if details.status == 500
+        ));
+    }
+
+    #[test]
+    fn test_decode_failure_without_error_object() {
+        let error = decode_failure(StatusCode::BAD_GATEWAY, r#"{"message":"upstream"}"#);
+        assert!(error.is_retryable());
+        assert_eq!(
+            error.details().unwrap().message,
+            r#"{"message":"upstream"}"#
+        );
+    }
+}
@@ -0,0 +1,11 @@
+mod client;
+pub use client::*;
+
+mod retry;
+pub use retry::*;
+
+mod rate_limit;
+pub use rate_limit::*;
+
+mod decode;
+pub(crate) use decode::*;
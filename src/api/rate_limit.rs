@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::TikTokApiError;
+
+/// A quota of `capacity` requests per `period`, refilled continuously.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+impl RateLimit {
+    /// Creates a quota of `capacity` requests per `period`.
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            period,
+        }
+    }
+
+    /// Creates a quota of `capacity` requests per second.
+    pub fn per_second(capacity: u32) -> Self {
+        Self::new(capacity, Duration::from_secs(1))
+    }
+
+    /// Creates a quota of `capacity` requests per minute.
+    pub fn per_minute(capacity: u32) -> Self {
+        Self::new(capacity, Duration::from_secs(60))
+    }
+
+    /// Creates a quota of `capacity` requests per day.
+    pub fn per_day(capacity: u32) -> Self {
+        Self::new(capacity, Duration::from_secs(24 * 60 * 60))
+    }
+
+    fn refill_rate(&self) -> f64 {
+        self.capacity as f64 / self.period.as_secs_f64()
+    }
+}
+
+/// What the limiter does when a bucket is empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RateLimitMode {
+    /// Wait until a request is available.
+    #[default]
+    Delay,
+    /// Fail immediately with `TikTokApiError::LocalRateLimited`.
+    Reject,
+}
+
+/// Number of buckets from which refilled buckets start being dropped.
+const MIN_SWEEP_LEN: usize = 64;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    /// Returns the requests available at `now`.
+    fn available(&self, limit: RateLimit, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        (self.tokens + elapsed * limit.refill_rate()).min(limit.capacity as f64)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    by_key: HashMap<(String, String), Bucket>,
+    /// The number of buckets at which the next sweep runs.
+    sweep_len: usize,
+}
+
+/// Client-side token bucket limiter keyed by endpoint and access token.
+///
+/// Every endpoint configured with a `RateLimit` gets one bucket per access token, so quotas
+/// that TikTok enforces per token are tracked separately. Requests to endpoints without a
+/// configured limit are not throttled. The limiter is shared by every clone of the
+/// `TikTokClient` it is attached to.
+///
+/// A bucket that has refilled completely behaves like a new one, so such buckets are dropped
+/// whenever the number of buckets has doubled, keeping memory bounded by the tokens in use.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    limits: HashMap<String, RateLimit>,
+    mode: RateLimitMode,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter without any configured endpoint.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a limiter with the quotas documented by TikTok for the endpoints of this crate.
+    pub fn tiktok_defaults() -> Self {
+        Self::new()
+            .with_limit("/v2/research/video/query/", RateLimit::per_day(1000))
+            .with_limit("/v2/research/user/info/", RateLimit::per_day(1000))
+            .with_limit("/v2/research/user/liked_videos/", RateLimit::per_day(1000))
+            .with_limit("/v2/research/user/pinned_videos/", RateLimit::per_day(1000))
+            .with_limit("/v2/research/user/followers/", RateLimit::per_day(1000))
+            .with_limit("/v2/research/user/following/", RateLimit::per_day(1000))
+            .with_limit(
+                "/v2/research/user/reposted_videos/",
+                RateLimit::per_day(1000),
+            )
+            .with_limit("/v2/research/video/comment/list/", RateLimit::per_day(1000))
+            .with_limit("/v2/user/info/", RateLimit::per_minute(600))
+            .with_limit("/v2/video/list/", RateLimit::per_minute(600))
+            .with_limit("/v2/video/query/", RateLimit::per_minute(600))
+            .with_limit(
+                "/v2/post/publish/creator_info/query/",
+                RateLimit::per_minute(20),
+            )
+            .with_limit("/v2/post/publish/video/init/", RateLimit::per_minute(6))
+            .with_limit("/v2/post/publish/content/init/", RateLimit::per_minute(6))
+            .with_limit("/v2/post/publish/status/fetch/", RateLimit::per_minute(30))
+    }
+
+    /// Sets the quota for an endpoint path such as `/v2/video/list/`.
+    pub fn with_limit(mut self, endpoint: &str, limit: RateLimit) -> Self {
+        self.limits.insert(endpoint.into(), limit);
+        self
+    }
+
+    /// Sets whether requests over quota are delayed or rejected.
+    pub fn with_mode(mut self, mode: RateLimitMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the quota configured for the given request path, if any. When several
+    /// configured endpoints match, the longest one wins.
+    pub fn limit_for(&self, path: &str) -> Option<(&str, RateLimit)> {
+        self.limits
+            .iter()
+            .filter(|(endpoint, _)| path.ends_with(endpoint.as_str()))
+            .max_by_key(|(endpoint, _)| endpoint.len())
+            .map(|(endpoint, limit)| (endpoint.as_str(), *limit))
+    }
+
+    /// Takes one request from the bucket of the endpoint and token, waiting or failing according
+    /// to the mode when the bucket is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the request URL.
+    /// * `token` - The access token the request is sent with.
+    pub async fn acquire(&self, path: &str, token: &str) -> Result<(), TikTokApiError> {
+        let Some((endpoint, limit)) = self.limit_for(path) else {
+            return Ok(());
+        };
+
+        loop {
+            let wait = self.try_acquire(endpoint, token, limit);
+            match (wait, self.mode) {
+                (None, _) => return Ok(()),
+                (Some(wait), RateLimitMode::Reject) => {
+                    return Err(TikTokApiError::LocalRateLimited(endpoint.into(), wait))
+                }
+                (Some(wait), RateLimitMode::Delay) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    /// Returns `None` when a request was taken, or how long until one is available.
+    fn try_acquire(&self, endpoint: &str, token: &str, limit: RateLimit) -> Option<Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        if buckets.by_key.len() >= buckets.sweep_len.max(MIN_SWEEP_LEN) {
+            self.sweep(&mut buckets, now);
+        }
+        let bucket = buckets
+            .by_key
+            .entry((endpoint.to_string(), token.to_string()))
+            .or_insert(Bucket {
+                tokens: limit.capacity as f64,
+                updated_at: now,
+            });
+
+        bucket.tokens = bucket.available(limit, now);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / limit.refill_rate(),
+            ))
+        }
+    }
+
+    /// Drops the buckets that have refilled completely.
+    fn sweep(&self, buckets: &mut Buckets, now: Instant) {
+        buckets.by_key.retain(|(endpoint, _), bucket| {
+            self.limits
+                .get(endpoint)
+                .is_some_and(|limit| bucket.available(*limit, now) < limit.capacity as f64)
+        });
+        buckets.sweep_len = buckets.by_key.len() * 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reject_mode_fails_once_bucket_is_empty() {
+        let limiter = RateLimiter::new()
+            .with_limit("/v2/video/list/", RateLimit::per_minute(2))
+            .with_mode(RateLimitMode::Reject);
+
+        assert!(limiter.acquire("/v2/video/list/", "a").await.is_ok());
+        assert!(limiter.acquire("/v2/video/list/", "a").await.is_ok());
+        assert!(matches!(
+            limiter.acquire("/v2/video/list/", "a").await,
+            Err(TikTokApiError::LocalRateLimited(_, _))
+        ));
+        // Other tokens and unconfigured endpoints have their own quota.
+        assert!(limiter.acquire("/v2/video/list/", "b").await.is_ok());
+        assert!(limiter.acquire("/v2/video/query/", "a").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delay_mode_waits_for_refill() {
+        let limiter = RateLimiter::new().with_limit("/v2/video/list/", RateLimit::per_second(20));
+
+        let started = Instant::now();
+        for _ in 0..21 {
+            limiter.acquire("/v2/video/list/", "a").await.unwrap();
+        }
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_limit_matches_path_with_base_url_prefix() {
+        let limiter = RateLimiter::tiktok_defaults();
+        let (endpoint, limit) = limiter.limit_for("/proxy/v2/video/list/").unwrap();
+        assert_eq!(endpoint, "/v2/video/list/");
+        assert_eq!(limit, RateLimit::per_minute(600));
+    }
+
+    #[test]
+    fn test_limit_prefers_longest_matching_endpoint() {
+        for _ in 0..10 {
+            let limiter = RateLimiter::new()
+                .with_limit("/list/", RateLimit::per_minute(1))
+                .with_limit("/v2/video/list/", RateLimit::per_minute(2))
+                .with_limit("/video/list/", RateLimit::per_minute(3));
+            let (endpoint, limit) = limiter.limit_for("/v2/video/list/").unwrap();
+            assert_eq!(endpoint, "/v2/video/list/");
+            assert_eq!(limit, RateLimit::per_minute(2));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refilled_buckets_are_dropped() {
+        let limiter = RateLimiter::new()
+            .with_limit("/v2/video/list/", RateLimit::per_second(1000))
+            .with_limit("/v2/user/info/", RateLimit::per_day(1));
+        limiter.acquire("/v2/user/info/", "busy").await.unwrap();
+        for token in 1..MIN_SWEEP_LEN {
+            limiter
+                .acquire("/v2/video/list/", &token.to_string())
+                .await
+                .unwrap();
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        limiter.acquire("/v2/video/list/", "new").await.unwrap();
+
+        let buckets = limiter.buckets.lock().unwrap();
+        let mut keys: Vec<_> = buckets
+            .by_key
+            .keys()
+            .map(|(_, token)| token.as_str())
+            .collect();
+        keys.sort();
+        assert_eq!(keys, ["busy", "new"]);
+        assert_eq!(buckets.sweep_len, 2);
+    }
+}
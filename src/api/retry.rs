@@ -0,0 +1,290 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::TikTokApiError;
+
+type Classifier = Arc<dyn Fn(&TikTokApiError) -> bool + Send + Sync>;
+type RetryHook = Arc<dyn Fn(&RetryEvent<'_>) + Send + Sync>;
+
+/// Information about a failed attempt that is about to be retried.
+#[derive(Debug)]
+pub struct RetryEvent<'a> {
+    /// The number of the attempt that just failed, starting at 1.
+    pub attempt: u32,
+    /// How long the client waits before the next attempt.
+    pub delay: Duration,
+    /// The error returned by the failed attempt.
+    pub error: &'a TikTokApiError,
+}
+
+/// Controls how requests are retried when TikTok or the network returns a transient error.
+///
+/// The delay before retry `n` is `base_delay * 2^(n - 1)`, capped at `max_delay`. With jitter
+/// enabled a random delay between half and the full value is used instead. A `Retry-After`
+/// header sent with the failed response takes precedence when `honor_retry_after` is set, unless
+/// it asks for a longer wait than `max_delay`, in which case the error is returned instead.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    max_elapsed: Option<Duration>,
+    honor_retry_after: bool,
+    classifier: Classifier,
+    on_retry: Option<RetryHook>,
+}
+
+impl RetryPolicy {
+    /// Creates the default policy: 3 attempts, 500ms base delay, 30s max delay, jitter enabled and
+    /// `Retry-After` honored.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            max_elapsed: None,
+            honor_retry_after: true,
+            classifier: Arc::new(is_retryable),
+            on_retry: None,
+        }
+    }
+
+    /// Creates a policy that never retries.
+    pub fn none() -> Self {
+        Self::new().with_max_attempts(1)
+    }
+
+    /// Sets the total number of attempts, including the first one.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Sets the upper bound for the computed backoff delay and for the wait requested by a
+    /// `Retry-After` header.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Enables or disables random jitter on the backoff delay.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Stops retrying once the next attempt would start after this much time since the first one.
+    pub fn with_max_elapsed(mut self, max_elapsed: Duration) -> Self {
+        self.max_elapsed = Some(max_elapsed);
+        self
+    }
+
+    /// Enables or disables waiting for the duration given in a `Retry-After` header.
+    pub fn with_honor_retry_after(mut self, honor_retry_after: bool) -> Self {
+        self.honor_retry_after = honor_retry_after;
+        self
+    }
+
+    /// Replaces the function deciding which errors are retried.
+    ///
+    /// The default classifier is `TikTokApiError::is_retryable`.
+    pub fn with_classifier<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&TikTokApiError) -> bool + Send + Sync + 'static,
+    {
+        self.classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Registers a hook called before every retry.
+    pub fn on_retry<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&RetryEvent<'_>) + Send + Sync + 'static,
+    {
+        self.on_retry = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns the total number of attempts, including the first one.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns whether the policy retries the given error.
+    pub fn is_retryable(&self, error: &TikTokApiError) -> bool {
+        (self.classifier)(error)
+    }
+
+    /// Computes the backoff delay before retrying after the given failed attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_delay);
+        if self.jitter && !delay.is_zero() {
+            let half = delay / 2;
+            half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+        } else {
+            delay
+        }
+    }
+
+    /// Returns how long to wait before the next attempt, or `None` if the error should be returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error returned by the failed attempt.
+    /// * `attempt` - The number of the failed attempt, starting at 1.
+    /// * `elapsed` - The time spent since the first attempt started.
+    /// * `retry_after` - The value of the `Retry-After` header of the failed response, if any.
+    pub(crate) fn next_delay(
+        &self,
+        error: &TikTokApiError,
+        attempt: u32,
+        elapsed: Duration,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.is_retryable(error) {
+            return None;
+        }
+
+        let delay = match retry_after {
+            Some(retry_after) if self.honor_retry_after => {
+                if retry_after > self.max_delay {
+                    return None;
+                }
+                retry_after
+            }
+            _ => self.backoff(attempt),
+        };
+
+        if let Some(max_elapsed) = self.max_elapsed {
+            if elapsed + delay > max_elapsed {
+                return None;
+            }
+        }
+
+        if let Some(hook) = &self.on_retry {
+            hook(&RetryEvent {
+                attempt,
+                delay,
+                error,
+            });
+        }
+
+        Some(delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("max_elapsed", &self.max_elapsed)
+            .field("honor_retry_after", &self.honor_retry_after)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The default retry classifier, see `TikTokApiError::is_retryable`.
+pub fn is_retryable(error: &TikTokApiError) -> bool {
+    error.is_retryable()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_exponentially_and_is_capped() {
+        let policy = RetryPolicy::new()
+            .with_jitter(false)
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(350));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let policy = RetryPolicy::new().with_base_delay(Duration::from_millis(100));
+        for _ in 0..100 {
+            let delay = policy.backoff(1);
+            assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn test_next_delay_respects_attempts_and_classifier() {
+        let policy = RetryPolicy::new().with_max_attempts(2).with_jitter(false);
+        let rate_limited = TikTokApiError::RateLimitExceeded(Box::default());
+        let invalid = TikTokApiError::InvalidParams(Box::default());
+
+        assert!(policy
+            .next_delay(&rate_limited, 1, Duration::ZERO, None)
+            .is_some());
+        assert!(policy
+            .next_delay(&rate_limited, 2, Duration::ZERO, None)
+            .is_none());
+        assert!(policy
+            .next_delay(&invalid, 1, Duration::ZERO, None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_next_delay_honors_retry_after_and_max_elapsed() {
+        let policy = RetryPolicy::new().with_max_elapsed(Duration::from_secs(10));
+        let error = TikTokApiError::RateLimitExceeded(Box::default());
+
+        assert_eq!(
+            policy.next_delay(&error, 1, Duration::ZERO, Some(Duration::from_secs(7))),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            policy.next_delay(
+                &error,
+                1,
+                Duration::from_secs(5),
+                Some(Duration::from_secs(7))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_delay_gives_up_on_retry_after_above_max_delay() {
+        let policy = RetryPolicy::new().with_max_delay(Duration::from_secs(30));
+        let error = TikTokApiError::RateLimitExceeded(Box::default());
+
+        assert_eq!(
+            policy.next_delay(&error, 1, Duration::ZERO, Some(Duration::from_secs(86_400))),
+            None
+        );
+        assert_eq!(
+            policy.next_delay(&error, 1, Duration::ZERO, Some(Duration::from_secs(30))),
+            Some(Duration::from_secs(30))
+        );
+    }
+}
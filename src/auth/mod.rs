@@ -3,3 +3,9 @@ pub use service::*;
 
 mod model;
 pub use model::*;
+
+mod token;
+pub use token::*;
+
+mod scope;
+pub use scope::*;
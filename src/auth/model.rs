@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use url::Url;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash)]
 pub enum TikTokScope {
     #[serde(rename = "artist.certification.read")]
     ArtistCertificationRead,
@@ -45,6 +45,29 @@ pub enum TikTokScope {
 }
 
 impl TikTokScope {
+    /// Every scope known to this crate.
+    pub const ALL: [TikTokScope; 19] = [
+        TikTokScope::ArtistCertificationRead,
+        TikTokScope::ArtistCertificationUpdate,
+        TikTokScope::PortabilityActivityOngoing,
+        TikTokScope::PortabilityActivitySingle,
+        TikTokScope::PortabilityAllOngoing,
+        TikTokScope::PortabilityAllSingle,
+        TikTokScope::PortabilityDirectMessagesOngoing,
+        TikTokScope::PortabilityDirectMessagesSingle,
+        TikTokScope::PortabilityPostsAndProfileOngoing,
+        TikTokScope::PortabilityPostsAndProfileSingle,
+        TikTokScope::ResearchAdlibBasic,
+        TikTokScope::ResearchDataBasic,
+        TikTokScope::ResearchDataU18EU,
+        TikTokScope::UserInfoBasic,
+        TikTokScope::UserInfoProfile,
+        TikTokScope::UserInfoStats,
+        TikTokScope::VideoList,
+        TikTokScope::VideoPublish,
+        TikTokScope::VideoUpload,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             TikTokScope::ArtistCertificationRead => "artist.certification.read",
@@ -163,7 +186,7 @@ fn generate_code_challenge(code_verifier: &str) -> String {
     general_purpose::URL_SAFE_NO_PAD.encode(result)
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccessTokenResponse {
     pub access_token: String,
     pub expires_in: i64,
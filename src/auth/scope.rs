@@ -0,0 +1,263 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::TikTokApiError;
+
+use super::{AccessTokenResponse, StoredToken, TikTokScope};
+
+impl fmt::Display for TikTokScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TikTokScope {
+    type Err = TikTokApiError;
+
+    fn from_str(scope: &str) -> Result<Self, Self::Err> {
+        TikTokScope::ALL
+            .into_iter()
+            .find(|known| known.as_str() == scope)
+            .ok_or_else(|| TikTokApiError::InvalidRequest(format!("Unknown scope: {}", scope)))
+    }
+}
+
+/// Parses the scopes granted to a token, as listed in `AccessTokenResponse.scope`, e.g.
+/// `user.info.basic,video.list`.
+///
+/// Scopes unknown to this crate are skipped.
+///
+/// # Arguments
+///
+/// * `scope` - The comma or whitespace separated scope list.
+pub fn parse_scopes(scope: &str) -> HashSet<TikTokScope> {
+    scope
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|scope| scope.parse().ok())
+        .collect()
+}
+
+/// Returns the `required` scopes that are not `granted`, in the order they are required and
+/// without duplicates.
+///
+/// # Arguments
+///
+/// * `granted` - The scopes the user granted.
+/// * `required` - The scopes an endpoint needs.
+pub fn missing_scopes(
+    granted: &HashSet<TikTokScope>,
+    required: &[TikTokScope],
+) -> Vec<TikTokScope> {
+    let mut missing = Vec::new();
+    for scope in required {
+        if !granted.contains(scope) && !missing.contains(scope) {
+            missing.push(*scope);
+        }
+    }
+    missing
+}
+
+/// Fails with `MissingScopes` if `granted` is known and lacks any of the `required` scopes.
+fn require_scopes(
+    granted: Option<&HashSet<TikTokScope>>,
+    required: &[TikTokScope],
+) -> Result<(), TikTokApiError> {
+    let Some(granted) = granted else {
+        return Ok(());
+    };
+    let missing = missing_scopes(granted, required);
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(TikTokApiError::MissingScopes(missing))
+    }
+}
+
+/// An access token passed to an API call, optionally with the scopes granted to it.
+///
+/// When the granted scopes are known, a call needing a scope that was not granted fails with
+/// `MissingScopes` without reaching TikTok. Plain strings convert into tokens whose scopes are
+/// unknown and never checked, while `StoredToken` and `AccessTokenResponse` carry their scopes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessToken<'a> {
+    access_token: &'a str,
+    scope: Option<&'a str>,
+}
+
+impl<'a> AccessToken<'a> {
+    /// Creates a token whose granted scopes are unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_token` - The access token.
+    pub fn new(access_token: &'a str) -> Self {
+        Self {
+            access_token,
+            scope: None,
+        }
+    }
+
+    /// Sets the scopes granted to the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The comma or whitespace separated scope list, as in
+    ///   `AccessTokenResponse.scope`.
+    pub fn with_scope(mut self, scope: &'a str) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Returns the access token.
+    pub fn as_str(&self) -> &'a str {
+        self.access_token
+    }
+
+    /// Returns the scopes granted to the token, if known.
+    pub fn granted_scopes(&self) -> Option<HashSet<TikTokScope>> {
+        self.scope.map(parse_scopes)
+    }
+
+    /// Fails with `MissingScopes` if the granted scopes are known and lack any of `required`.
+    pub(crate) fn require(&self, required: &[TikTokScope]) -> Result<(), TikTokApiError> {
+        require_scopes(self.granted_scopes().as_ref(), required)
+    }
+}
+
+impl<'a> From<&'a str> for AccessToken<'a> {
+    fn from(access_token: &'a str) -> Self {
+        Self::new(access_token)
+    }
+}
+
+impl<'a> From<&&'a str> for AccessToken<'a> {
+    fn from(access_token: &&'a str) -> Self {
+        Self::new(access_token)
+    }
+}
+
+impl<'a> From<&'a String> for AccessToken<'a> {
+    fn from(access_token: &'a String) -> Self {
+        Self::new(access_token)
+    }
+}
+
+impl<'a> From<&'a StoredToken> for AccessToken<'a> {
+    fn from(token: &'a StoredToken) -> Self {
+        Self::new(&token.access_token).with_scope(&token.scope)
+    }
+}
+
+impl<'a> From<&'a AccessTokenResponse> for AccessToken<'a> {
+    fn from(token: &'a AccessTokenResponse) -> Self {
+        Self::new(&token.access_token).with_scope(&token.scope)
+    }
+}
+
+/// An `AccessToken` owned by a stream.
+#[derive(Debug, Clone)]
+pub(crate) struct OwnedAccessToken {
+    access_token: String,
+    scope: Option<String>,
+}
+
+impl From<AccessToken<'_>> for OwnedAccessToken {
+    fn from(token: AccessToken<'_>) -> Self {
+        Self {
+            access_token: token.access_token.to_string(),
+            scope: token.scope.map(str::to_string),
+        }
+    }
+}
+
+impl OwnedAccessToken {
+    pub(crate) fn as_token(&self) -> AccessToken<'_> {
+        AccessToken {
+            access_token: &self.access_token,
+            scope: self.scope.as_deref(),
+        }
+    }
+}
+
+impl AccessTokenResponse {
+    /// Returns the scopes granted to this token.
+    pub fn granted_scopes(&self) -> HashSet<TikTokScope> {
+        parse_scopes(&self.scope)
+    }
+}
+
+impl StoredToken {
+    /// Returns the scopes granted to this token.
+    pub fn granted_scopes(&self) -> HashSet<TikTokScope> {
+        parse_scopes(&self.scope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scopes_skips_unknown_scopes() {
+        let scopes = parse_scopes("user.info.basic,video.list, video.upload,unknown.scope");
+
+        assert_eq!(
+            scopes,
+            HashSet::from([
+                TikTokScope::UserInfoBasic,
+                TikTokScope::VideoList,
+                TikTokScope::VideoUpload,
+            ])
+        );
+        assert!(parse_scopes("").is_empty());
+        assert!("unknown.scope".parse::<TikTokScope>().is_err());
+        for scope in TikTokScope::ALL {
+            assert_eq!(scope.as_str().parse::<TikTokScope>().unwrap(), scope);
+        }
+    }
+
+    #[test]
+    fn test_require_scopes_lists_each_missing_scope_once() {
+        let granted = HashSet::from([TikTokScope::UserInfoBasic]);
+        let required = [
+            TikTokScope::UserInfoStats,
+            TikTokScope::UserInfoBasic,
+            TikTokScope::VideoList,
+            TikTokScope::UserInfoStats,
+        ];
+
+        assert_eq!(
+            missing_scopes(&granted, &required),
+            vec![TikTokScope::UserInfoStats, TikTokScope::VideoList]
+        );
+        match require_scopes(Some(&granted), &required) {
+            Err(TikTokApiError::MissingScopes(missing)) => {
+                assert_eq!(
+                    missing,
+                    vec![TikTokScope::UserInfoStats, TikTokScope::VideoList]
+                )
+            }
+            other => panic!("expected MissingScopes, got {:?}", other),
+        }
+        assert!(require_scopes(Some(&granted), &[TikTokScope::UserInfoBasic]).is_ok());
+        assert!(require_scopes(None, &required).is_ok());
+    }
+
+    #[test]
+    fn test_access_token_checks_only_known_scopes() {
+        let required = [TikTokScope::VideoList];
+        assert!(AccessToken::from("token").require(&required).is_ok());
+
+        let token = AccessToken::new("token").with_scope("user.info.basic");
+        assert_eq!(token.as_str(), "token");
+        assert!(matches!(
+            token.require(&required),
+            Err(TikTokApiError::MissingScopes(missing)) if missing == required
+        ));
+        assert!(OwnedAccessToken::from(token)
+            .as_token()
+            .require(&[TikTokScope::UserInfoBasic])
+            .is_ok());
+    }
+}
@@ -1,22 +1,38 @@
-use reqwest::Client;
+use crate::api::{decode_empty, decode_json, TikTokClient};
 
-use crate::error::{ErrorResponse, TikTokApiError};
+use crate::error::TikTokApiError;
 
 use super::{AccessTokenResponse, AuthCallback, OAuthConfig};
 
+#[derive(Clone)]
 pub struct AuthService {
     config: OAuthConfig,
-    client: Client,
-    base_url: String,
+    client: TikTokClient,
 }
 
 impl AuthService {
     pub fn new(config: OAuthConfig) -> Self {
-        Self {
-            config,
-            client: Client::new(),
-            base_url: String::from("https://open.tiktokapis.com"),
-        }
+        Self::from_client(TikTokClient::new(), config)
+    }
+
+    /// Creates a new `AuthService` sharing the given `TikTokClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client whose connection pool and configuration are reused.
+    /// * `config` - The OAuth configuration of the app.
+    pub fn from_client(client: TikTokClient, config: OAuthConfig) -> Self {
+        Self { config, client }
+    }
+
+    /// Sets a custom base URL for the AuthService.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - A string slice that holds the custom base URL.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.client = self.client.with_base_url(base_url);
+        self
     }
 
     pub fn get_authorization_url(&self) -> String {
@@ -59,7 +75,7 @@ impl AuthService {
         redirect_uri: &str,
         code_verifier: Option<&str>,
     ) -> Result<AccessTokenResponse, TikTokApiError> {
-        let url = format!("{}/v2/oauth/token/", self.base_url);
+        let url = self.client.url("/v2/oauth/token/");
 
         let client_key = self.config.client_key.clone();
         let client_secret = self.config.client_secret.clone();
@@ -79,30 +95,16 @@ impl AuthService {
             params.push(("code_verifier", verifier.to_string()));
         }
 
-        let response = self
+        let request = self
             .client
+            .http()
             .post(&url)
             .header("Content-Type", "application/x-www-form-urlencoded")
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .form(&params);
 
-        let status = response.status();
-        let body = response
-            .text()
+        self.client
+            .execute_once(request, decode_json::<AccessTokenResponse>)
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        if status.is_success() {
-            let token_response: AccessTokenResponse = serde_json::from_str(&body)
-                .map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-            Ok(token_response)
-        } else {
-            let error_response: ErrorResponse = serde_json::from_str(&body)
-                .map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-            Err(TikTokApiError::from(error_response))
-        }
     }
 
     /// Refreshes an access token using a refresh token.
@@ -118,7 +120,7 @@ impl AuthService {
         &self,
         refresh_token: &str,
     ) -> Result<AccessTokenResponse, TikTokApiError> {
-        let url = format!("{}/v2/oauth/token/", self.base_url);
+        let url = self.client.url("/v2/oauth/token/");
 
         let params = [
             ("client_key", &self.config.client_key),
@@ -127,29 +129,44 @@ impl AuthService {
             ("refresh_token", &refresh_token.to_string()),
         ];
 
-        let response = self
+        let request = self
             .client
+            .http()
             .post(&url)
             .header("Content-Type", "application/x-www-form-urlencoded")
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .form(&params);
 
-        let status = response.status();
-        let body = response
-            .text()
+        self.client
+            .execute_once(request, decode_json::<AccessTokenResponse>)
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        if status.is_success() {
-            let token_response: AccessTokenResponse = serde_json::from_str(&body)
-                .map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-            Ok(token_response)
-        } else {
-            let error_response: ErrorResponse = serde_json::from_str(&body)
-                .map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-            Err(TikTokApiError::from(error_response))
-        }
+    }
+
+    /// Revokes an access token, deauthorizing the app for the user. The refresh token of the
+    /// user stops working as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_token` - The user's access token.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), TikTokApiError>` - Nothing on success, or an error.
+    pub async fn revoke_access_token(&self, access_token: &str) -> Result<(), TikTokApiError> {
+        let url = self.client.url("/v2/oauth/revoke/");
+
+        let params = [
+            ("client_key", self.config.client_key.as_str()),
+            ("client_secret", self.config.client_secret.as_str()),
+            ("token", access_token),
+        ];
+
+        let request = self
+            .client
+            .http()
+            .post(&url)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .form(&params);
+
+        self.client.execute(request, decode_empty).await
     }
 }
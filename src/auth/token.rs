@@ -0,0 +1,669 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+use crate::error::TikTokApiError;
+
+use super::{AccessTokenResponse, AuthService};
+
+/// The default time before expiry at which `TokenManager` refreshes an access token.
+pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// A user token pair with absolute expiry times, as kept by a `TokenStore`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StoredToken {
+    pub open_id: String,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub scope: String,
+    pub token_type: String,
+    /// Unix timestamp in seconds at which the access token expires.
+    pub expires_at: i64,
+    /// Unix timestamp in seconds at which the refresh token expires.
+    pub refresh_expires_at: i64,
+}
+
+impl StoredToken {
+    /// Converts a token response into a stored token, resolving the relative lifetimes
+    /// against `now`.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response of a token exchange or refresh.
+    /// * `now` - The Unix timestamp in seconds at which the response was received.
+    pub fn from_response(response: AccessTokenResponse, now: i64) -> Self {
+        Self {
+            open_id: response.open_id,
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            scope: response.scope,
+            token_type: response.token_type,
+            expires_at: now + response.expires_in,
+            refresh_expires_at: now + response.refresh_expires_in,
+        }
+    }
+
+    /// Returns true if the access token expires within `margin` of `now`.
+    pub fn expires_within(&self, margin: Duration, now: i64) -> bool {
+        self.expires_at - now <= margin.as_secs() as i64
+    }
+
+    /// Returns true if the refresh token has expired at `now`.
+    pub fn is_refresh_expired(&self, now: i64) -> bool {
+        self.refresh_expires_at <= now
+    }
+}
+
+/// Persistence for user tokens, keyed by `open_id`.
+#[async_trait]
+pub trait TokenStore: Send + Sync {
+    /// Loads the token of `open_id`, if any.
+    async fn load(&self, open_id: &str) -> Result<Option<StoredToken>, TikTokApiError>;
+
+    /// Saves `token`, replacing any token stored for the same `open_id`.
+    async fn save(&self, token: &StoredToken) -> Result<(), TikTokApiError>;
+
+    /// Removes the token of `open_id`. Removing a missing token is not an error.
+    async fn remove(&self, open_id: &str) -> Result<(), TikTokApiError>;
+}
+
+/// A `TokenStore` that keeps tokens in memory for the lifetime of the process.
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    tokens: RwLock<HashMap<String, StoredToken>>,
+}
+
+impl MemoryTokenStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TokenStore for MemoryTokenStore {
+    async fn load(&self, open_id: &str) -> Result<Option<StoredToken>, TikTokApiError> {
+        Ok(self.tokens.read().unwrap().get(open_id).cloned())
+    }
+
+    async fn save(&self, token: &StoredToken) -> Result<(), TikTokApiError> {
+        self.tokens
+            .write()
+            .unwrap()
+            .insert(token.open_id.clone(), token.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, open_id: &str) -> Result<(), TikTokApiError> {
+        self.tokens.write().unwrap().remove(open_id);
+        Ok(())
+    }
+}
+
+/// A `TokenStore` that keeps all tokens in a single JSON file.
+///
+/// The file is rewritten through a temporary file that is synced to disk before it replaces
+/// the store, so a crash never leaves it half written. On Unix the file is only readable and
+/// writable by its owner. A missing file is treated as an empty store.
+#[derive(Debug)]
+pub struct FileTokenStore {
+    path: PathBuf,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl FileTokenStore {
+    /// Creates a store backed by the JSON file at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read and write. It is created on the first save.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Returns the path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn read_all(&self) -> Result<HashMap<String, StoredToken>, TikTokApiError> {
+        match tokio::fs::read(&self.path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| TikTokApiError::TokenStoreFailed(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(TikTokApiError::TokenStoreFailed(e.to_string())),
+        }
+    }
+
+    async fn write_all(&self, tokens: &HashMap<String, StoredToken>) -> Result<(), TikTokApiError> {
+        let bytes = serde_json::to_vec_pretty(tokens)
+            .map_err(|e| TikTokApiError::TokenStoreFailed(e.to_string()))?;
+        let tmp_path = self.path.with_extension("tmp");
+        write_private(&tmp_path, &bytes)
+            .await
+            .map_err(|e| TikTokApiError::TokenStoreFailed(e.to_string()))?;
+        tokio::fs::rename(&tmp_path, &self.path)
+            .await
+            .map_err(|e| TikTokApiError::TokenStoreFailed(e.to_string()))
+    }
+}
+
+/// Writes `bytes` to `path` with owner-only permissions on Unix and syncs them to disk.
+pub(crate) async fn write_private(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(path).await?;
+    // A leftover file from an earlier run keeps its permissions, so set them again.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .await?;
+    }
+    file.write_all(bytes).await?;
+    file.sync_all().await
+}
+
+#[async_trait]
+impl TokenStore for FileTokenStore {
+    async fn load(&self, open_id: &str) -> Result<Option<StoredToken>, TikTokApiError> {
+        let _guard = self.lock.lock().await;
+        Ok(self.read_all().await?.remove(open_id))
+    }
+
+    async fn save(&self, token: &StoredToken) -> Result<(), TikTokApiError> {
+        let _guard = self.lock.lock().await;
+        let mut tokens = self.read_all().await?;
+        tokens.insert(token.open_id.clone(), token.clone());
+        self.write_all(&tokens).await
+    }
+
+    async fn remove(&self, open_id: &str) -> Result<(), TikTokApiError> {
+        let _guard = self.lock.lock().await;
+        let mut tokens = self.read_all().await?;
+        if tokens.remove(open_id).is_some() {
+            self.write_all(&tokens).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Keeps user tokens in a `TokenStore` and refreshes them before they expire.
+///
+/// Concurrent callers asking for the same `open_id` while its token is being refreshed wait
+/// for that single refresh instead of starting their own, since TikTok invalidates the
+/// previous refresh token once it has been used. Clones share the store and the refresh state.
+#[derive(Clone)]
+pub struct TokenManager {
+    auth: AuthService,
+    store: Arc<dyn TokenStore>,
+    refresh_margin: Duration,
+    refresh_locks: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+impl TokenManager {
+    /// Creates a manager that refreshes tokens through `auth` and persists them in `store`.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - The service used to refresh tokens.
+    /// * `store` - Where tokens are kept.
+    pub fn new(auth: AuthService, store: Arc<dyn TokenStore>) -> Self {
+        Self {
+            auth,
+            store,
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+            refresh_locks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sets how long before expiry an access token is refreshed.
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh_margin` - The margin, `DEFAULT_REFRESH_MARGIN` by default.
+    pub fn with_refresh_margin(mut self, refresh_margin: Duration) -> Self {
+        self.refresh_margin = refresh_margin;
+        self
+    }
+
+    /// Returns the underlying token store.
+    pub fn store(&self) -> &Arc<dyn TokenStore> {
+        &self.store
+    }
+
+    /// Stores the token of a completed token exchange or refresh.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The token response returned by TikTok.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<StoredToken, TikTokApiError>` - The stored token or an error.
+    pub async fn insert(
+        &self,
+        response: AccessTokenResponse,
+    ) -> Result<StoredToken, TikTokApiError> {
+        let token = StoredToken::from_response(response, Utc::now().timestamp());
+        self.store.save(&token).await?;
+        Ok(token)
+    }
+
+    /// Exchanges an authorization code for a token and stores it.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The authorization code from the callback.
+    /// * `redirect_uri` - The redirect URI used for requesting the code.
+    /// * `code_verifier` - The code verifier used in PKCE authorization flow (optional).
+    ///
+    /// # Returns
+    ///
+    /// * `Result<StoredToken, TikTokApiError>` - The stored token or an error.
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        redirect_uri: &str,
+        code_verifier: Option<&str>,
+    ) -> Result<StoredToken, TikTokApiError> {
+        let response = self
+            .auth
+            .fetch_access_token(code, redirect_uri, code_verifier)
+            .await?;
+        self.insert(response).await
+    }
+
+    /// Returns a valid access token for `open_id`, refreshing it first if it expires within
+    /// the refresh margin.
+    ///
+    /// # Arguments
+    ///
+    /// * `open_id` - The user whose token is requested.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, TikTokApiError>` - The bearer token or an error.
+    pub async fn access_token(&self, open_id: &str) -> Result<String, TikTokApiError> {
+        Ok(self.token(open_id).await?.access_token)
+    }
+
+    /// Returns the stored token of `open_id`, refreshing it first if its access token expires
+    /// within the refresh margin.
+    ///
+    /// # Arguments
+    ///
+    /// * `open_id` - The user whose token is requested.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<StoredToken, TikTokApiError>` - The valid token or an error.
+    pub async fn token(&self, open_id: &str) -> Result<StoredToken, TikTokApiError> {
+        let token = self.load(open_id).await?;
+        if !token.expires_within(self.refresh_margin, Utc::now().timestamp()) {
+            return Ok(token);
+        }
+
+        let lock = self.refresh_lock(open_id);
+        let _guard = lock.lock().await;
+
+        // Another caller may have refreshed the token while this one was waiting.
+        let token = self.load(open_id).await?;
+        if !token.expires_within(self.refresh_margin, Utc::now().timestamp()) {
+            return Ok(token);
+        }
+        self.refresh_stored(token).await
+    }
+
+    /// Refreshes the token of `open_id` regardless of its expiry, e.g. after TikTok rejected
+    /// it with `AccessTokenInvalid`.
+    ///
+    /// # Arguments
+    ///
+    /// * `open_id` - The user whose token is refreshed.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<StoredToken, TikTokApiError>` - The refreshed token or an error.
+    pub async fn refresh(&self, open_id: &str) -> Result<StoredToken, TikTokApiError> {
+        let lock = self.refresh_lock(open_id);
+        let _guard = lock.lock().await;
+
+        let token = self.load(open_id).await?;
+        self.refresh_stored(token).await
+    }
+
+    /// Revokes the token of `open_id` and removes it from the store, e.g. when the user
+    /// disconnects their TikTok account or asks for their data to be deleted.
+    ///
+    /// An access token that expires within the refresh margin is refreshed first, so TikTok
+    /// accepts the revocation. If TikTok reports that the token is already expired, invalid or
+    /// revoked, e.g. because the user removed the app, the authorization is gone and the token
+    /// is only removed. If TikTok fails to revoke the token for any other reason, it is kept so
+    /// the call can be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `open_id` - The user whose token is revoked.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), TikTokApiError>` - Nothing on success, or an error.
+    pub async fn revoke(&self, open_id: &str) -> Result<(), TikTokApiError> {
+        let lock = self.refresh_lock(open_id);
+        let _guard = lock.lock().await;
+
+        let mut token = self.load(open_id).await?;
+        if token.expires_within(self.refresh_margin, Utc::now().timestamp()) {
+            token = match self.refresh_stored(token).await {
+                Ok(token) => token,
+                Err(error) if is_already_revoked(&error) => {
+                    return self.store.remove(open_id).await
+                }
+                Err(error) => return Err(error),
+            };
+        }
+        match self.auth.revoke_access_token(&token.access_token).await {
+            Ok(()) => {}
+            Err(error) if is_already_revoked(&error) => {}
+            Err(error) => return Err(error),
+        }
+        self.store.remove(open_id).await
+    }
+
+    async fn load(&self, open_id: &str) -> Result<StoredToken, TikTokApiError> {
+        self.store
+            .load(open_id)
+            .await?
+            .ok_or_else(|| TikTokApiError::TokenNotFound(open_id.to_string()))
+    }
+
+    async fn refresh_stored(&self, token: StoredToken) -> Result<StoredToken, TikTokApiError> {
+        if token.is_refresh_expired(Utc::now().timestamp()) {
+            return Err(TikTokApiError::RefreshTokenExpired(token.open_id));
+        }
+        let response = self.auth.refresh_access_token(&token.refresh_token).await?;
+        self.insert(response).await
+    }
+
+    fn refresh_lock(&self, open_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.refresh_locks
+            .lock()
+            .unwrap()
+            .entry(open_id.to_string())
+            .or_default()
+            .clone()
+    }
+}
+
+/// Returns true if TikTok rejected a token because the authorization is already gone.
+fn is_already_revoked(error: &TikTokApiError) -> bool {
+    match error {
+        TikTokApiError::AccessTokenInvalid(_) | TikTokApiError::RefreshTokenExpired(_) => true,
+        TikTokApiError::ApiError(details) => {
+            matches!(details.code.as_str(), "invalid_grant" | "invalid_token")
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::auth::OAuthConfig;
+
+    fn stored(open_id: &str, expires_in: i64) -> StoredToken {
+        let now = Utc::now().timestamp();
+        StoredToken {
+            open_id: open_id.to_string(),
+            access_token: "old-access".to_string(),
+            refresh_token: "old-refresh".to_string(),
+            scope: "user.info.basic".to_string(),
+            token_type: "Bearer".to_string(),
+            expires_at: now + expires_in,
+            refresh_expires_at: now + 86_400,
+        }
+    }
+
+    fn manager(server: &MockServer, store: Arc<dyn TokenStore>) -> TokenManager {
+        let config = OAuthConfig::new("key", "secret", "https://example.com/callback", &[]);
+        TokenManager::new(AuthService::new(config).with_base_url(&server.uri()), store)
+    }
+
+    async fn mock_refresh(server: &MockServer, expected_calls: u64) {
+        Mock::given(method("POST"))
+            .and(path("/v2/oauth/token/"))
+            .and(body_string_contains("refresh_token=old-refresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "access_token": "new-access",
+                "expires_in": 86400,
+                "open_id": "user",
+                "refresh_expires_in": 31536000,
+                "refresh_token": "new-refresh",
+                "scope": "user.info.basic",
+                "token_type": "Bearer"
+            })))
+            .expect(expected_calls)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_fresh_token_is_returned_without_refresh() {
+        let server = MockServer::start().await;
+        mock_refresh(&server, 0).await;
+        let store = Arc::new(MemoryTokenStore::new());
+        store.save(&stored("user", 3600)).await.unwrap();
+
+        let manager = manager(&server, store);
+        assert_eq!(manager.access_token("user").await.unwrap(), "old-access");
+    }
+
+    #[tokio::test]
+    async fn test_expiring_token_is_refreshed_once_for_concurrent_callers() {
+        let server = MockServer::start().await;
+        mock_refresh(&server, 1).await;
+        let store = Arc::new(MemoryTokenStore::new());
+        store.save(&stored("user", 10)).await.unwrap();
+
+        let manager = manager(&server, store.clone());
+        let tokens = futures::future::join_all((0..5).map(|_| manager.access_token("user"))).await;
+        for token in tokens {
+            assert_eq!(token.unwrap(), "new-access");
+        }
+
+        let saved = store.load("user").await.unwrap().unwrap();
+        assert_eq!(saved.refresh_token, "new-refresh");
+        assert!(!saved.expires_within(DEFAULT_REFRESH_MARGIN, Utc::now().timestamp()));
+    }
+
+    #[tokio::test]
+    async fn test_missing_and_unrefreshable_tokens() {
+        let server = MockServer::start().await;
+        mock_refresh(&server, 0).await;
+        let store = Arc::new(MemoryTokenStore::new());
+        let mut token = stored("user", -10);
+        token.refresh_expires_at = Utc::now().timestamp() - 1;
+        store.save(&token).await.unwrap();
+
+        let manager = manager(&server, store);
+        assert!(matches!(
+            manager.access_token("other").await,
+            Err(TikTokApiError::TokenNotFound(id)) if id == "other"
+        ));
+        assert!(matches!(
+            manager.access_token("user").await,
+            Err(TikTokApiError::RefreshTokenExpired(_))
+        ));
+    }
+
+    async fn mock_revoke(server: &MockServer, response: ResponseTemplate, expected_calls: u64) {
+        Mock::given(method("POST"))
+            .and(path("/v2/oauth/revoke/"))
+            .and(body_string_contains("client_key=key"))
+            .and(body_string_contains("token=old-access"))
+            .respond_with(response)
+            .expect(expected_calls)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_revoke_removes_token_from_store() {
+        let server = MockServer::start().await;
+        mock_revoke(&server, ResponseTemplate::new(200), 1).await;
+        let store = Arc::new(MemoryTokenStore::new());
+        store.save(&stored("user", 3600)).await.unwrap();
+        store.save(&stored("other", 3600)).await.unwrap();
+
+        let manager = manager(&server, store.clone());
+        manager.revoke("user").await.unwrap();
+
+        assert_eq!(store.load("user").await.unwrap(), None);
+        assert!(store.load("other").await.unwrap().is_some());
+        assert!(matches!(
+            manager.revoke("user").await,
+            Err(TikTokApiError::TokenNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_revoke_keeps_token() {
+        let server = MockServer::start().await;
+        let error = ResponseTemplate::new(400).set_body_json(json!({
+            "error": "invalid_request",
+            "error_description": "The request parameters are malformed.",
+            "log_id": "log"
+        }));
+        mock_revoke(&server, error, 1).await;
+        let store = Arc::new(MemoryTokenStore::new());
+        store.save(&stored("user", 3600)).await.unwrap();
+
+        let manager = manager(&server, store.clone());
+        match manager.revoke("user").await {
+            Err(TikTokApiError::ApiError(details)) => {
+                assert_eq!(details.code, "invalid_request");
+                assert_eq!(details.path, "/v2/oauth/revoke/");
+            }
+            other => panic!("expected ApiError, got {:?}", other),
+        }
+        assert!(store.load("user").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_purges_token_whose_refresh_token_expired() {
+        let server = MockServer::start().await;
+        mock_revoke(&server, ResponseTemplate::new(200), 0).await;
+        let store = Arc::new(MemoryTokenStore::new());
+        let mut token = stored("user", -10);
+        token.refresh_expires_at = Utc::now().timestamp() - 1;
+        store.save(&token).await.unwrap();
+
+        manager(&server, store.clone())
+            .revoke("user")
+            .await
+            .unwrap();
+        assert_eq!(store.load("user").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_purges_token_that_is_already_invalid() {
+        let server = MockServer::start().await;
+        let error = ResponseTemplate::new(401).set_body_json(json!({
+            "error": "invalid_token",
+            "error_description": "The access token is invalid or has been revoked.",
+            "log_id": "log"
+        }));
+        mock_revoke(&server, error, 1).await;
+        let store = Arc::new(MemoryTokenStore::new());
+        store.save(&stored("user", 3600)).await.unwrap();
+
+        manager(&server, store.clone())
+            .revoke("user")
+            .await
+            .unwrap();
+        assert_eq!(store.load("user").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_purges_token_whose_refresh_token_was_revoked() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/oauth/token/"))
+            .and(body_string_contains("grant_type=refresh_token"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": "invalid_grant",
+                "error_description": "Refresh token is invalid or expired.",
+                "log_id": "log"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        mock_revoke(&server, ResponseTemplate::new(200), 0).await;
+        let store = Arc::new(MemoryTokenStore::new());
+        store.save(&stored("user", -10)).await.unwrap();
+
+        manager(&server, store.clone())
+            .revoke("user")
+            .await
+            .unwrap();
+        assert_eq!(store.load("user").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_keeps_refresh_lock_for_waiting_callers() {
+        let server = MockServer::start().await;
+        mock_revoke(&server, ResponseTemplate::new(200), 1).await;
+        let store = Arc::new(MemoryTokenStore::new());
+        store.save(&stored("user", 3600)).await.unwrap();
+
+        let manager = manager(&server, store.clone());
+        let lock = manager.refresh_lock("user");
+        manager.revoke("user").await.unwrap();
+        assert!(Arc::ptr_eq(&lock, &manager.refresh_lock("user")));
+    }
+
+    #[tokio::test]
+    async fn test_file_store_round_trip() {
+        let path = std::env::temp_dir().join(format!("tiktok_tokens_{}.json", std::process::id()));
+        let store = FileTokenStore::new(&path);
+        assert_eq!(store.load("user").await.unwrap(), None);
+
+        let token = stored("user", 3600);
+        store.save(&token).await.unwrap();
+        store.save(&stored("other", 3600)).await.unwrap();
+        assert_eq!(
+            FileTokenStore::new(&path).load("user").await.unwrap(),
+            Some(token)
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        store.remove("user").await.unwrap();
+        assert_eq!(store.load("user").await.unwrap(), None);
+        assert!(store.load("other").await.unwrap().is_some());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+use crate::error::TikTokApiError;
+
+use super::{Service, TokenResponse};
+
+/// The default time before expiry at which `TokenCache` fetches a new client access token.
+pub const DEFAULT_CLIENT_TOKEN_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug)]
+struct CachedToken {
+    response: TokenResponse,
+    expires_at: Instant,
+}
+
+/// Caches the client access token of a `client::Service` until shortly before it expires.
+///
+/// Concurrent callers share a single `client_credentials` grant when the token has to be
+/// fetched. Clones share the cached token.
+#[derive(Clone)]
+pub struct TokenCache {
+    service: Service,
+    refresh_margin: Duration,
+    cached: Arc<Mutex<Option<CachedToken>>>,
+}
+
+impl TokenCache {
+    /// Creates an empty cache that fetches tokens through `service`.
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - The service performing the `client_credentials` grant.
+    pub fn new(service: Service) -> Self {
+        Self {
+            service,
+            refresh_margin: DEFAULT_CLIENT_TOKEN_MARGIN,
+            cached: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Sets how long before expiry a new token is fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh_margin` - The margin, `DEFAULT_CLIENT_TOKEN_MARGIN` by default.
+    pub fn with_refresh_margin(mut self, refresh_margin: Duration) -> Self {
+        self.refresh_margin = refresh_margin;
+        self
+    }
+
+    /// Returns the cached token response, fetching a new one if none is cached or the cached
+    /// one expires within the refresh margin.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `TokenResponse` on success, or a `TikTokApiError` on failure.
+    pub async fn token(&self) -> Result<TokenResponse, TikTokApiError> {
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.expires_at > Instant::now() + self.refresh_margin {
+                return Ok(token.response.clone());
+            }
+        }
+
+        let fetched_at = Instant::now();
+        let response = self.service.get_access_token().await?;
+        let lifetime = Duration::from_secs(response.expires_in.max(0) as u64);
+        *cached = Some(CachedToken {
+            response: response.clone(),
+            expires_at: fetched_at + lifetime,
+        });
+        Ok(response)
+    }
+
+    /// Returns a valid client access token.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the bearer token on success, or a `TikTokApiError` on failure.
+    pub async fn access_token(&self) -> Result<String, TikTokApiError> {
+        Ok(self.token().await?.access_token)
+    }
+
+    /// Drops the cached token, e.g. after TikTok rejected it, so the next call fetches a new one.
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+
+    /// Drops the cached token only if it is still `access_token`.
+    ///
+    /// Concurrent callers whose requests were rejected with the same token then trigger a
+    /// single fetch, instead of discarding the token another caller has just fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_token` - The token TikTok rejected.
+    pub async fn invalidate_token(&self, access_token: &str) {
+        let mut cached = self.cached.lock().await;
+        if cached
+            .as_ref()
+            .is_some_and(|token| token.response.access_token == access_token)
+        {
+            *cached = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    async fn mock_token(server: &MockServer, expires_in: i64, expected_calls: u64) {
+        Mock::given(method("POST"))
+            .and(path("/v2/oauth/token/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "access_token": "client-token",
+                "expires_in": expires_in,
+                "token_type": "Bearer"
+            })))
+            .expect(expected_calls)
+            .mount(server)
+            .await;
+    }
+
+    fn cache(server: &MockServer) -> TokenCache {
+        TokenCache::new(Service::with_credentials("key", "secret").with_base_url(&server.uri()))
+    }
+
+    #[tokio::test]
+    async fn test_token_is_fetched_once_for_concurrent_callers() {
+        let server = MockServer::start().await;
+        mock_token(&server, 7200, 1).await;
+        let cache = cache(&server);
+
+        let tokens = futures::future::join_all((0..5).map(|_| cache.access_token())).await;
+        for token in tokens {
+            assert_eq!(token.unwrap(), "client-token");
+        }
+        assert_eq!(cache.clone().access_token().await.unwrap(), "client-token");
+    }
+
+    #[tokio::test]
+    async fn test_expiring_or_invalidated_token_is_fetched_again() {
+        let server = MockServer::start().await;
+        mock_token(&server, 60, 3).await;
+        let cache = cache(&server);
+
+        // A 60 second token is always within the default margin.
+        cache.access_token().await.unwrap();
+        cache.access_token().await.unwrap();
+
+        let cache = cache.with_refresh_margin(Duration::ZERO);
+        cache.invalidate().await;
+        cache.access_token().await.unwrap();
+        cache.access_token().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_token_keeps_a_newer_token() {
+        let server = MockServer::start().await;
+        mock_token(&server, 7200, 2).await;
+        let cache = cache(&server);
+
+        cache.access_token().await.unwrap();
+        cache.invalidate_token("stale-token").await;
+        cache.access_token().await.unwrap();
+
+        cache.invalidate_token("client-token").await;
+        cache.access_token().await.unwrap();
+    }
+}
@@ -3,3 +3,6 @@ pub use service::*;
 
 mod model;
 pub use model::*;
+
+mod cache;
+pub use cache::*;
@@ -1,15 +1,16 @@
 use std::env;
 
-use reqwest::Client;
+use crate::api::{decode_json, TikTokClient};
 
-use crate::error::{ErrorResponse, TikTokApiError};
+use crate::error::TikTokApiError;
 
 use super::TokenResponse;
 
+#[derive(Clone)]
 pub struct Service {
     client_key: String,
     client_secret: String,
-    base_url: String,
+    client: TikTokClient,
 }
 
 impl Service {
@@ -18,6 +19,7 @@ impl Service {
     /// # Panics
     ///
     /// Panics if the `TIKTOK_CLIENT_KEY` or `TIKTOK_CLIENT_SECRET` environment variables are not set.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         let client_key = env::var("TIKTOK_CLIENT_KEY").expect("TIKTOK_CLIENT_KEY must be set");
         let client_secret =
@@ -25,7 +27,7 @@ impl Service {
         Self {
             client_key,
             client_secret,
-            base_url: String::from("https://open.tiktokapis.com"),
+            client: TikTokClient::new(),
         }
     }
 
@@ -39,7 +41,23 @@ impl Service {
         Self {
             client_key: client_key.into(),
             client_secret: client_secret.into(),
-            base_url: String::from("https://open.tiktokapis.com"),
+            client: TikTokClient::new(),
+        }
+    }
+
+    /// Creates a new instance of the Service with the provided client key and secret, sharing the
+    /// given `TikTokClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client whose connection pool and configuration are reused.
+    /// * `client_key` - A string slice that holds the client key.
+    /// * `client_secret` - A string slice that holds the client secret.
+    pub fn from_client(client: TikTokClient, client_key: &str, client_secret: &str) -> Self {
+        Self {
+            client_key: client_key.into(),
+            client_secret: client_secret.into(),
+            client,
         }
     }
 
@@ -49,7 +67,7 @@ impl Service {
     ///
     /// * `base_url` - A string slice that holds the custom base URL.
     pub fn with_base_url(mut self, base_url: &str) -> Self {
-        self.base_url = base_url.into();
+        self.client = self.client.with_base_url(base_url);
         self
     }
 
@@ -59,8 +77,7 @@ impl Service {
     ///
     /// A `Result` containing a `TokenResponse` on success, or a `TikTokApiError` on failure.
     pub async fn get_access_token(&self) -> Result<TokenResponse, TikTokApiError> {
-        let client = Client::new();
-        let url = format!("{}/v2/oauth/token/", self.base_url);
+        let url = self.client.url("/v2/oauth/token/");
 
         let params = [
             ("client_key", &self.client_key),
@@ -68,28 +85,15 @@ impl Service {
             ("grant_type", &String::from("client_credentials")),
         ];
 
-        let response = client
+        let request = self
+            .client
+            .http()
             .post(&url)
             .header("Content-Type", "application/x-www-form-urlencoded")
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .form(&params);
 
-        let status = response.status();
-        let body = response
-            .text()
+        self.client
+            .execute(request, decode_json::<TokenResponse>)
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        if status.is_success() {
-            let token_response: TokenResponse = serde_json::from_str(&body)
-                .map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-            Ok(token_response)
-        } else {
-            let error_response: ErrorResponse = serde_json::from_str(&body)
-                .map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-            Err(TikTokApiError::from(error_response))
-        }
     }
 }
@@ -30,6 +30,7 @@ pub struct CreatorData {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -1,17 +1,35 @@
 use super::{CreatorData, CreatorInfoResponse};
+use crate::api::{decode_envelope, TikTokClient};
+use crate::auth::{AccessToken, TikTokScope};
 use crate::error::TikTokApiError;
-use reqwest::Client;
 
+#[derive(Clone)]
 pub struct Service {
-    base_url: String,
+    client: TikTokClient,
+}
+
+impl Default for Service {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Service {
+    /// The scopes `get_creator_info` needs.
+    pub const CREATOR_INFO_SCOPES: &'static [TikTokScope] = &[TikTokScope::VideoPublish];
+
     /// Creates a new instance of the Service.
     pub fn new() -> Self {
-        Self {
-            base_url: String::from("https://open.tiktokapis.com"),
-        }
+        Self::from_client(TikTokClient::new())
+    }
+
+    /// Creates a new instance of the Service sharing the given `TikTokClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client whose connection pool and configuration are reused.
+    pub fn from_client(client: TikTokClient) -> Self {
+        Self { client }
     }
 
     /// Sets a custom base URL for the Service.
@@ -20,7 +38,7 @@ impl Service {
     ///
     /// * `base_url` - A string slice that holds the custom base URL.
     pub fn with_base_url(mut self, base_url: &str) -> Self {
-        self.base_url = base_url.into();
+        self.client = self.client.with_base_url(base_url);
         self
     }
 
@@ -28,36 +46,35 @@ impl Service {
     ///
     /// # Arguments
     ///
-    /// * `token` - The API token.
+    /// * `token` - The API token, with its granted scopes if known.
     ///
     /// # Returns
     ///
     /// A `Result` containing a `CreatorData` on success, or a `TikTokApiError` on failure.
-    pub async fn get_creator_info(&self, token: &str) -> Result<CreatorData, TikTokApiError> {
-        let url = format!("{}/v2/post/publish/creator_info/query/", self.base_url);
-        let client = Client::new();
+    pub async fn get_creator_info(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+    ) -> Result<CreatorData, TikTokApiError> {
+        let token = token.into();
+        token.require(Self::CREATOR_INFO_SCOPES)?;
+
+        let url = self.client.url("/v2/post/publish/creator_info/query/");
 
-        let response = client
+        let request = self
+            .client
+            .http()
             .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json; charset=UTF-8")
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .header("Authorization", format!("Bearer {}", token.as_str()))
+            .header("Content-Type", "application/json; charset=UTF-8");
 
-        let status = response.status();
-        let body = response
-            .text()
+        self.client
+            .execute(request, |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |creator_info_response: CreatorInfoResponse| creator_info_response.data,
+                )
+            })
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let creator_info_response: CreatorInfoResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-
-        if status.is_success() && creator_info_response.error.code == "ok" {
-            Ok(creator_info_response.data)
-        } else {
-            Err(TikTokApiError::from(creator_info_response.error))
-        }
     }
 }
@@ -0,0 +1,293 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use crate::api::decode_envelope;
+use crate::auth::{AccessToken, TikTokScope};
+use crate::error::TikTokApiError;
+
+use super::service::file_size;
+use super::{
+    ChunkPlan, PostStatusData, PublishStatus, Service, Source, SourceInfo, SourceInfoBuilder,
+    VideoInitResponse, VideoInitResponseData,
+};
+
+/// The request initializing an upload to the creator's inbox, where it waits as a draft until
+/// the creator edits and posts it. Requires the `video.upload` scope.
+#[derive(Clone, Serialize, Deserialize, Debug, Builder)]
+#[builder(setter(into))]
+pub struct InboxVideoInitRequest {
+    pub source_info: SourceInfo,
+}
+
+/// The response of an inbox init request, which has the same shape as a direct post init.
+pub type InboxVideoInitResponse = VideoInitResponse;
+
+impl Service {
+    /// The scopes `init_inbox_video` and the inbox upload functions need.
+    pub const INBOX_INIT_SCOPES: &'static [TikTokScope] = &[TikTokScope::VideoUpload];
+
+    /// Initializes a video upload to the creator's inbox.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `inbox_init_request` - The request data for initializing the upload.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<VideoInitResponseData, TikTokApiError>` - The response data or an error.
+    pub async fn init_inbox_video(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        inbox_init_request: InboxVideoInitRequest,
+    ) -> Result<VideoInitResponseData, TikTokApiError> {
+        let token = token.into();
+        token.require(Self::INBOX_INIT_SCOPES)?;
+
+        let url = self.client.url("/v2/post/publish/inbox/video/init/");
+
+        let request = self
+            .client
+            .http()
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token.as_str()))
+            .header("Content-Type", "application/json; charset=UTF-8")
+            .json(&inbox_init_request);
+
+        self.client
+            .execute_once(request, |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |inbox_init_response: InboxVideoInitResponse| inbox_init_response.data,
+                )
+            })
+            .await
+    }
+
+    /// Simplified function to upload a video file to the creator's inbox.
+    ///
+    /// This function combines the steps of initializing an inbox upload, uploading the video
+    /// file in chunks, and waiting until the video reaches the inbox into a single function call.
+    /// The status is polled according to the Service's `PollPolicy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `file_path` - The path to the video file on the local filesystem.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PostStatusData, TikTokApiError>` - The status data, normally
+    ///   `SEND_TO_USER_INBOX`, or an error.
+    pub async fn upload_video_to_inbox_from_file(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        file_path: &str,
+    ) -> Result<PostStatusData, TikTokApiError> {
+        let token = token.into();
+        let plan = ChunkPlan::for_size(file_size(file_path).await?)?;
+        let inbox_init_request = InboxVideoInitRequest {
+            source_info: plan.source_info(),
+        };
+        let response_data = self.init_inbox_video(token, inbox_init_request).await?;
+
+        let tracker = self.tracker(&plan, &[]);
+        self.upload_chunks(&response_data.upload_url, file_path, &plan, &tracker)
+            .await?;
+
+        self.wait_tracked(
+            token,
+            &response_data.publish_id,
+            self.poll_policy,
+            PublishStatus::is_inbox_final,
+            Some(&tracker),
+        )
+        .await
+    }
+
+    /// Simplified function to upload a video from a URL to the creator's inbox.
+    ///
+    /// This function combines the steps of initializing an inbox upload and waiting until
+    /// TikTok has downloaded the video to the inbox into a single function call.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `video_url` - The URL of the video, on a domain verified for the app.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PostStatusData, TikTokApiError>` - The status data, normally
+    ///   `SEND_TO_USER_INBOX`, or an error.
+    pub async fn upload_video_to_inbox_from_url(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        video_url: &str,
+    ) -> Result<PostStatusData, TikTokApiError> {
+        let token = token.into();
+        let source_info = SourceInfoBuilder::default()
+            .source(Source::PullFromUrl)
+            .video_url(Some(video_url.to_string()))
+            .build()
+            .unwrap();
+        let inbox_init_request = InboxVideoInitRequest { source_info };
+        let response_data = self.init_inbox_video(token, inbox_init_request).await?;
+
+        self.wait_tracked(
+            token,
+            &response_data.publish_id,
+            self.poll_policy,
+            PublishStatus::is_inbox_final,
+            None,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::direct_post::PollPolicy;
+
+    #[tokio::test]
+    async fn test_upload_video_to_inbox_from_url_waits_for_inbox() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/inbox/video/init/"))
+            .and(body_json(json!({
+                "source_info": {"source": "PULL_FROM_URL", "video_url": "https://example.com/a.mp4"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"publish_id": "inbox.123"},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/status/fetch/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"status": "PROCESSING_DOWNLOAD"},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/status/fetch/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"status": "SEND_TO_USER_INBOX"},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .with_priority(2)
+            .mount(&server)
+            .await;
+
+        let status = Service::new()
+            .with_base_url(&server.uri())
+            .with_poll_policy(PollPolicy::new().with_initial_interval(Duration::from_millis(1)))
+            .upload_video_to_inbox_from_url("token", "https://example.com/a.mp4")
+            .await
+            .unwrap();
+        assert_eq!(status.status, PublishStatus::SendToUserInbox);
+    }
+
+    #[tokio::test]
+    async fn test_upload_video_to_inbox_from_file_uploads_chunks_and_waits_for_inbox() {
+        const MB: u64 = 1024 * 1024;
+        let video_size = 21 * MB;
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/inbox/video/init/"))
+            .and(body_json(json!({
+                "source_info": {
+                    "source": "FILE_UPLOAD",
+                    "video_size": video_size,
+                    "chunk_size": 10 * MB,
+                    "total_chunk_count": 2
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"publish_id": "inbox.123", "upload_url": format!("{}/upload", server.uri())},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        for (range, status) in [
+            (format!("bytes 0-{}/{}", 10 * MB - 1, video_size), 206),
+            (
+                format!("bytes {}-{}/{}", 10 * MB, video_size - 1, video_size),
+                201,
+            ),
+        ] {
+            Mock::given(method("PUT"))
+                .and(path("/upload"))
+                .and(header("Content-Range", range.as_str()))
+                .respond_with(ResponseTemplate::new(status))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/status/fetch/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"status": "PROCESSING_UPLOAD"},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/status/fetch/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"status": "SEND_TO_USER_INBOX"},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .with_priority(2)
+            .mount(&server)
+            .await;
+
+        let file_path = std::env::temp_dir().join(format!("inbox_{}.mp4", std::process::id()));
+        std::fs::write(&file_path, vec![0u8; video_size as usize]).unwrap();
+        let status = Service::new()
+            .with_base_url(&server.uri())
+            .with_poll_policy(PollPolicy::new().with_initial_interval(Duration::from_millis(1)))
+            .upload_video_to_inbox_from_file("token", file_path.to_str().unwrap())
+            .await;
+        std::fs::remove_file(&file_path).unwrap();
+        assert_eq!(status.unwrap().status, PublishStatus::SendToUserInbox);
+    }
+
+    #[tokio::test]
+    async fn test_inbox_init_is_not_retried_on_gateway_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/inbox/video/init/"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("<html>Unavailable</html>"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = Service::new()
+            .with_base_url(&server.uri())
+            .upload_video_to_inbox_from_url("token", "https://example.com/a.mp4")
+            .await;
+        match result {
+            Err(error @ TikTokApiError::NonJsonResponse(_)) => {
+                assert_eq!(error.details().unwrap().status, 503);
+                assert!(error.is_retryable());
+            }
+            other => panic!("expected NonJsonResponse, got {:?}", other),
+        }
+    }
+}
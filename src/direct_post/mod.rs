@@ -3,3 +3,23 @@ pub use model::*;
 
 mod service;
 pub use service::*;
+
+mod upload;
+pub use upload::*;
+
+mod progress;
+pub use progress::*;
+
+mod poll;
+pub use poll::*;
+
+mod inbox;
+pub use inbox::*;
+
+mod photo;
+pub use photo::*;
+
+mod preflight;
+pub use preflight::*;
+
+mod source;
@@ -1,6 +1,9 @@
+use std::fmt;
+
 use derive_builder::Builder;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
+use crate::auth::TikTokScope;
 use crate::error::ErrorResponse;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -10,7 +13,7 @@ pub enum Source {
     PullFromUrl,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PrivacyLevel {
     PublicToEveryone,
@@ -19,13 +22,42 @@ pub enum PrivacyLevel {
     SelfOnly,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+impl PrivacyLevel {
+    /// Returns the value TikTok uses for this privacy level, as listed in
+    /// `CreatorData::privacy_level_options`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrivacyLevel::PublicToEveryone => "PUBLIC_TO_EVERYONE",
+            PrivacyLevel::MutualFollowFriends => "MUTUAL_FOLLOW_FRIENDS",
+            PrivacyLevel::FollowerOfCreator => "FOLLOWER_OF_CREATOR",
+            PrivacyLevel::SelfOnly => "SELF_ONLY",
+        }
+    }
+}
+
+impl fmt::Display for PrivacyLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PostMode {
     DirectPost,
     MediaUpload,
 }
 
+impl PostMode {
+    /// Returns the scope needed to initialize a post in this mode.
+    pub fn scope(&self) -> TikTokScope {
+        match self {
+            PostMode::DirectPost => TikTokScope::VideoPublish,
+            PostMode::MediaUpload => TikTokScope::VideoUpload,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MediaType {
@@ -43,6 +75,36 @@ pub struct PostInfo {
     pub video_cover_timestamp_ms: u64,
 }
 
+/// The post information of a photo post.
+///
+/// Only `title` and `description` apply to `PostMode::MediaUpload`, where the creator sets the
+/// remaining options in the TikTok app. `privacy_level` is required for `PostMode::DirectPost`.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, Builder)]
+#[builder(setter(into), default)]
+pub struct PhotoPostInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy_level: Option<PrivacyLevel>,
+    pub disable_comment: bool,
+    pub auto_add_music: bool,
+    pub brand_content_toggle: bool,
+    pub brand_organic_toggle: bool,
+}
+
+impl From<PostInfo> for PhotoPostInfo {
+    fn from(post_info: PostInfo) -> Self {
+        Self {
+            title: Some(post_info.title),
+            privacy_level: Some(post_info.privacy_level),
+            disable_comment: post_info.disable_comment,
+            ..Self::default()
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, Builder)]
 #[builder(setter(into))]
 pub struct SourceInfo {
@@ -92,22 +154,240 @@ pub struct VideoInitResponse {
 #[derive(Clone, Serialize, Deserialize, Debug, Builder)]
 #[builder(setter(into))]
 pub struct PhotoInitRequest {
-    pub post_info: PostInfo,
+    pub post_info: PhotoPostInfo,
     pub source_info: SourceInfo,
     pub post_mode: PostMode,
     pub media_type: MediaType,
 }
 
+impl PhotoInitRequest {
+    /// Creates a photo init request.
+    ///
+    /// # Arguments
+    ///
+    /// * `post_info` - The post information, either a `PhotoPostInfo` or a `PostInfo`.
+    /// * `source_info` - The source of the photos.
+    /// * `post_mode` - Whether to post directly or to the creator's inbox.
+    /// * `media_type` - The type of the media.
+    pub fn new(
+        post_info: impl Into<PhotoPostInfo>,
+        source_info: SourceInfo,
+        post_mode: PostMode,
+        media_type: MediaType,
+    ) -> Self {
+        Self {
+            post_info: post_info.into(),
+            source_info,
+            post_mode,
+            media_type,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PostStatusResponse {
+    #[serde(default)]
     pub data: PostStatusData,
     pub error: ErrorResponse,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
 pub struct PostStatusData {
+    #[serde(default)]
     pub publish_id: String,
-    pub status: String,
+    #[serde(default)]
+    pub status: PublishStatus,
+    #[serde(
+        default,
+        deserialize_with = "empty_as_none",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fail_reason: Option<FailReason>,
+    /// The IDs of the public posts, once published. The misspelling is TikTok's.
+    #[serde(default)]
+    pub publicaly_available_post_id: Vec<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uploaded_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downloaded_bytes: Option<u64>,
+}
+
+/// The status of a post as reported by TikTok.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum PublishStatus {
+    ProcessingUpload,
+    ProcessingDownload,
+    SendToUserInbox,
+    PublishComplete,
+    Failed,
+    /// A status this crate does not know yet.
+    Unknown(String),
+}
+
+impl PublishStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            PublishStatus::ProcessingUpload => "PROCESSING_UPLOAD",
+            PublishStatus::ProcessingDownload => "PROCESSING_DOWNLOAD",
+            PublishStatus::SendToUserInbox => "SEND_TO_USER_INBOX",
+            PublishStatus::PublishComplete => "PUBLISH_COMPLETE",
+            PublishStatus::Failed => "FAILED",
+            PublishStatus::Unknown(status) => status,
+        }
+    }
+
+    /// Returns true if TikTok no longer changes the status of the post.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, PublishStatus::PublishComplete | PublishStatus::Failed)
+    }
+
+    /// Returns true once an inbox upload has reached the creator's inbox or failed. The status
+    /// only changes afterwards if the creator publishes the draft.
+    pub fn is_inbox_final(&self) -> bool {
+        self.is_terminal() || *self == PublishStatus::SendToUserInbox
+    }
+}
+
+impl Default for PublishStatus {
+    fn default() -> Self {
+        PublishStatus::Unknown(String::new())
+    }
+}
+
+impl From<String> for PublishStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "PROCESSING_UPLOAD" => PublishStatus::ProcessingUpload,
+            "PROCESSING_DOWNLOAD" => PublishStatus::ProcessingDownload,
+            "SEND_TO_USER_INBOX" => PublishStatus::SendToUserInbox,
+            "PUBLISH_COMPLETE" => PublishStatus::PublishComplete,
+            "FAILED" => PublishStatus::Failed,
+            _ => PublishStatus::Unknown(status),
+        }
+    }
+}
+
+impl From<PublishStatus> for String {
+    fn from(status: PublishStatus) -> Self {
+        status.as_str().to_string()
+    }
+}
+
+impl fmt::Display for PublishStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Why TikTok failed to publish a post.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum FailReason {
+    /// The file format is not supported.
+    FileFormatCheckFailed,
+    /// The video is too long or too short.
+    DurationCheckFailed,
+    /// The frame rate is not supported.
+    FrameRateCheckFailed,
+    /// The picture size is not supported.
+    PictureSizeCheckFailed,
+    /// TikTok failed internally and the post can be retried.
+    Internal,
+    /// TikTok could not download the video from the given URL.
+    VideoPullFailed,
+    /// TikTok could not download a photo from the given URL.
+    PhotoPullFailed,
+    /// The developer cancelled the upload.
+    PublishCancelled,
+    /// The user removed the app's access while the post was processed.
+    AuthRemoved,
+    /// The user has reached the daily post limit.
+    SpamRiskTooManyPosts,
+    /// The user is banned from posting.
+    SpamRiskUserBannedFromPosting,
+    /// The caption was flagged as spam.
+    SpamRiskText,
+    /// The post was flagged as spam.
+    SpamRisk,
+    /// A reason this crate does not know yet.
+    Unknown(String),
+}
+
+impl FailReason {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FailReason::FileFormatCheckFailed => "file_format_check_failed",
+            FailReason::DurationCheckFailed => "duration_check_failed",
+            FailReason::FrameRateCheckFailed => "frame_rate_check_failed",
+            FailReason::PictureSizeCheckFailed => "picture_size_check_failed",
+            FailReason::Internal => "internal",
+            FailReason::VideoPullFailed => "video_pull_failed",
+            FailReason::PhotoPullFailed => "photo_pull_failed",
+            FailReason::PublishCancelled => "publish_cancelled",
+            FailReason::AuthRemoved => "auth_removed",
+            FailReason::SpamRiskTooManyPosts => "spam_risk_too_many_posts",
+            FailReason::SpamRiskUserBannedFromPosting => "spam_risk_user_banned_from_posting",
+            FailReason::SpamRiskText => "spam_risk_text",
+            FailReason::SpamRisk => "spam_risk",
+            FailReason::Unknown(reason) => reason,
+        }
+    }
+
+    /// Returns true if the media itself was rejected and has to be changed before posting again.
+    pub fn is_media_rejected(&self) -> bool {
+        matches!(
+            self,
+            FailReason::FileFormatCheckFailed
+                | FailReason::DurationCheckFailed
+                | FailReason::FrameRateCheckFailed
+                | FailReason::PictureSizeCheckFailed
+        )
+    }
+}
+
+impl From<String> for FailReason {
+    fn from(reason: String) -> Self {
+        match reason.as_str() {
+            "file_format_check_failed" => FailReason::FileFormatCheckFailed,
+            "duration_check_failed" => FailReason::DurationCheckFailed,
+            "frame_rate_check_failed" => FailReason::FrameRateCheckFailed,
+            "picture_size_check_failed" => FailReason::PictureSizeCheckFailed,
+            "internal" => FailReason::Internal,
+            "video_pull_failed" => FailReason::VideoPullFailed,
+            "photo_pull_failed" => FailReason::PhotoPullFailed,
+            "publish_cancelled" => FailReason::PublishCancelled,
+            "auth_removed" => FailReason::AuthRemoved,
+            "spam_risk_too_many_posts" => FailReason::SpamRiskTooManyPosts,
+            "spam_risk_user_banned_from_posting" => FailReason::SpamRiskUserBannedFromPosting,
+            "spam_risk_text" => FailReason::SpamRiskText,
+            "spam_risk" => FailReason::SpamRisk,
+            _ => FailReason::Unknown(reason),
+        }
+    }
+}
+
+impl From<FailReason> for String {
+    fn from(reason: FailReason) -> Self {
+        reason.as_str().to_string()
+    }
+}
+
+impl fmt::Display for FailReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// TikTok sends an empty `fail_reason` for posts that have not failed.
+fn empty_as_none<'de, D>(deserializer: D) -> Result<Option<FailReason>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let reason: Option<String> = Option::deserialize(deserializer)?;
+    Ok(reason
+        .filter(|reason| !reason.is_empty())
+        .map(FailReason::from))
 }
 
 #[cfg(test)]
@@ -131,4 +411,86 @@ mod tests {
         assert_eq!(response.data.upload_url, "http://example.com/upload");
         assert_eq!(response.error.code, "none");
     }
+
+    #[test]
+    fn test_deserialize_post_status() {
+        let json_data = r#"{"data":{"status":"FAILED","fail_reason":"duration_check_failed","publicaly_available_post_id":[],"uploaded_bytes":10000},"error":{"code":"ok","message":"","log_id":"log"}}"#;
+        let response: PostStatusResponse = serde_json::from_str(json_data).unwrap();
+        assert_eq!(response.data.status, PublishStatus::Failed);
+        assert!(response.data.status.is_terminal());
+        assert_eq!(
+            response.data.fail_reason,
+            Some(FailReason::DurationCheckFailed)
+        );
+        assert_eq!(response.data.uploaded_bytes, Some(10000));
+        assert_eq!(response.data.downloaded_bytes, None);
+
+        let json_data = r#"{"data":{"status":"PUBLISH_COMPLETE","fail_reason":"","publicaly_available_post_id":[7412345678901234567]},"error":{"code":"ok","message":"","log_id":"log"}}"#;
+        let response: PostStatusResponse = serde_json::from_str(json_data).unwrap();
+        assert_eq!(response.data.status, PublishStatus::PublishComplete);
+        assert_eq!(response.data.fail_reason, None);
+        assert_eq!(
+            response.data.publicaly_available_post_id,
+            [7412345678901234567]
+        );
+    }
+
+    #[test]
+    fn test_photo_init_request_accepts_post_info() {
+        let post_info = PostInfo {
+            title: "Trip".to_string(),
+            privacy_level: PrivacyLevel::SelfOnly,
+            disable_duet: false,
+            disable_comment: true,
+            disable_stitch: false,
+            video_cover_timestamp_ms: 0,
+        };
+        let source_info = SourceInfoBuilder::default()
+            .source(Source::PullFromUrl)
+            .photo_cover_index(Some(0))
+            .photo_images(Some(vec!["https://example.com/1.jpg".to_string()]))
+            .build()
+            .unwrap();
+
+        let request = PhotoInitRequest::new(
+            post_info.clone(),
+            source_info.clone(),
+            PostMode::DirectPost,
+            MediaType::Photo,
+        );
+        assert_eq!(request.post_info.title.as_deref(), Some("Trip"));
+        assert_eq!(
+            request.post_info.privacy_level,
+            Some(PrivacyLevel::SelfOnly)
+        );
+        assert!(request.post_info.disable_comment);
+
+        let built = PhotoInitRequestBuilder::default()
+            .post_info(post_info)
+            .source_info(source_info)
+            .post_mode(PostMode::DirectPost)
+            .media_type(MediaType::Photo)
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&built).unwrap(),
+            serde_json::to_value(&request).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_status_and_reason_are_kept() {
+        let data: PostStatusData =
+            serde_json::from_str(r#"{"status":"NEW_STATUS","fail_reason":"new_reason"}"#).unwrap();
+        assert_eq!(data.status, PublishStatus::Unknown("NEW_STATUS".into()));
+        assert!(!data.status.is_terminal());
+        assert_eq!(
+            data.fail_reason,
+            Some(FailReason::Unknown("new_reason".into()))
+        );
+        assert_eq!(
+            serde_json::to_value(&data).unwrap()["fail_reason"],
+            "new_reason"
+        );
+    }
 }
@@ -0,0 +1,239 @@
+use derive_builder::Builder;
+
+use crate::auth::AccessToken;
+use crate::error::TikTokApiError;
+
+use super::{
+    MediaType, PhotoInitRequest, PhotoPostInfo, PostMode, PostStatusData, PublishStatus, Service,
+    Source, SourceInfo,
+};
+
+/// The maximum number of images in a photo post.
+pub const MAX_PHOTO_COUNT: usize = 35;
+
+/// A photo carousel post, validated when built.
+#[derive(Clone, Debug, Builder)]
+#[builder(setter(into), build_fn(validate = "Self::validate"))]
+pub struct PhotoPost {
+    pub post_info: PhotoPostInfo,
+    /// The public URLs of the images, on a domain verified for the app.
+    pub photo_images: Vec<String>,
+    /// The zero-based index of the cover image in `photo_images`.
+    #[builder(default)]
+    pub photo_cover_index: u32,
+    #[builder(default = "PostMode::DirectPost")]
+    pub post_mode: PostMode,
+}
+
+impl PhotoPostBuilder {
+    fn validate(&self) -> Result<(), String> {
+        let privacy_level = self
+            .post_info
+            .as_ref()
+            .and_then(|post_info| post_info.privacy_level.as_ref());
+        check_photo_post(
+            self.photo_images.as_deref().unwrap_or_default(),
+            self.photo_cover_index.unwrap_or_default(),
+            self.post_mode.unwrap_or(PostMode::DirectPost),
+            privacy_level.is_some(),
+        )
+    }
+}
+
+impl PhotoPost {
+    /// Checks the image count, the cover index, and that a direct post has a privacy level.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), TikTokApiError>` - `InvalidRequest` describing the first problem found.
+    pub fn validate(&self) -> Result<(), TikTokApiError> {
+        check_photo_post(
+            &self.photo_images,
+            self.photo_cover_index,
+            self.post_mode,
+            self.post_info.privacy_level.is_some(),
+        )
+        .map_err(TikTokApiError::InvalidRequest)
+    }
+
+    /// Converts the post into the request body of `Service::post_photo`.
+    pub fn into_request(self) -> PhotoInitRequest {
+        PhotoInitRequest {
+            post_info: self.post_info,
+            source_info: SourceInfo {
+                source: Source::PullFromUrl,
+                video_size: None,
+                chunk_size: None,
+                total_chunk_count: None,
+                video_url: None,
+                photo_cover_index: Some(self.photo_cover_index),
+                photo_images: Some(self.photo_images),
+            },
+            post_mode: self.post_mode,
+            media_type: MediaType::Photo,
+        }
+    }
+}
+
+fn check_photo_post(
+    photo_images: &[String],
+    photo_cover_index: u32,
+    post_mode: PostMode,
+    has_privacy_level: bool,
+) -> Result<(), String> {
+    if photo_images.is_empty() {
+        return Err("a photo post needs at least one image".to_string());
+    }
+    if photo_images.len() > MAX_PHOTO_COUNT {
+        return Err(format!(
+            "a photo post takes at most {} images, got {}",
+            MAX_PHOTO_COUNT,
+            photo_images.len()
+        ));
+    }
+    if photo_cover_index as usize >= photo_images.len() {
+        return Err(format!(
+            "photo_cover_index {} is out of range for {} images",
+            photo_cover_index,
+            photo_images.len()
+        ));
+    }
+    if post_mode == PostMode::DirectPost && !has_privacy_level {
+        return Err("a direct photo post needs a privacy_level".to_string());
+    }
+    Ok(())
+}
+
+impl Service {
+    /// Simplified function to publish a photo post.
+    ///
+    /// This function validates the post, initializes it, and waits for it to reach a final
+    /// status according to the Service's `PollPolicy`. Posts in `PostMode::MediaUpload` are
+    /// final once they reach the creator's inbox.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `photo_post` - The photo post.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PostStatusData, TikTokApiError>` - The status data or an error.
+    pub async fn publish_photo_post(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        photo_post: PhotoPost,
+    ) -> Result<PostStatusData, TikTokApiError> {
+        let token = token.into();
+        photo_post.validate()?;
+        let is_final = match photo_post.post_mode {
+            PostMode::DirectPost => PublishStatus::is_terminal,
+            PostMode::MediaUpload => PublishStatus::is_inbox_final,
+        };
+
+        let response_data = self.post_photo(token, photo_post.into_request()).await?;
+
+        self.wait_tracked(
+            token,
+            &response_data.publish_id,
+            self.poll_policy,
+            is_final,
+            None,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::direct_post::{PhotoPostInfoBuilder, PollPolicy, PrivacyLevel};
+
+    fn images(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| format!("https://example.com/{}.jpg", i))
+            .collect()
+    }
+
+    fn direct_post_info() -> PhotoPostInfo {
+        PhotoPostInfoBuilder::default()
+            .privacy_level(Some(PrivacyLevel::SelfOnly))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_builder_validates_images_cover_and_privacy() {
+        let build = |count: usize, cover: u32, post_info: PhotoPostInfo, mode: PostMode| {
+            PhotoPostBuilder::default()
+                .post_info(post_info)
+                .photo_images(images(count))
+                .photo_cover_index(cover)
+                .post_mode(mode)
+                .build()
+        };
+
+        assert!(build(35, 34, direct_post_info(), PostMode::DirectPost).is_ok());
+        assert!(build(36, 0, direct_post_info(), PostMode::DirectPost).is_err());
+        assert!(build(0, 0, direct_post_info(), PostMode::DirectPost).is_err());
+        assert!(build(2, 2, direct_post_info(), PostMode::DirectPost).is_err());
+        assert!(build(2, 1, PhotoPostInfo::default(), PostMode::DirectPost).is_err());
+        assert!(build(2, 1, PhotoPostInfo::default(), PostMode::MediaUpload).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_media_upload_post_waits_for_inbox() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/content/init/"))
+            .and(body_partial_json(json!({
+                "post_info": {"description": "Summer", "auto_add_music": true},
+                "source_info": {"source": "PULL_FROM_URL", "photo_cover_index": 0},
+                "post_mode": "MEDIA_UPLOAD",
+                "media_type": "PHOTO"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"publish_id": "photo.1"},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/status/fetch/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"status": "SEND_TO_USER_INBOX"},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let post = PhotoPostBuilder::default()
+            .post_info(
+                PhotoPostInfoBuilder::default()
+                    .description(Some("Summer".to_string()))
+                    .auto_add_music(true)
+                    .build()
+                    .unwrap(),
+            )
+            .photo_images(images(3))
+            .post_mode(PostMode::MediaUpload)
+            .build()
+            .unwrap();
+
+        let status = Service::new()
+            .with_base_url(&server.uri())
+            .with_poll_policy(PollPolicy::new().with_initial_interval(Duration::from_millis(1)))
+            .publish_photo_post("token", post)
+            .await
+            .unwrap();
+        assert_eq!(status.status, PublishStatus::SendToUserInbox);
+    }
+}
@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+/// Controls how the status of a post is polled while TikTok processes it.
+///
+/// The delay before poll `n + 1` is `initial_interval * multiplier^(n - 1)`, capped at
+/// `max_interval`. Polling gives up once `timeout` has elapsed since the first poll.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PollPolicy {
+    initial_interval: Duration,
+    max_interval: Duration,
+    multiplier: f64,
+    timeout: Duration,
+}
+
+impl Default for PollPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PollPolicy {
+    /// Creates the default policy: 2s initial interval growing by 1.5x up to 30s, with a
+    /// 10 minute timeout.
+    pub fn new() -> Self {
+        Self {
+            initial_interval: Duration::from_secs(2),
+            max_interval: Duration::from_secs(30),
+            multiplier: 1.5,
+            timeout: Duration::from_secs(10 * 60),
+        }
+    }
+
+    /// Sets the delay between the first and second poll.
+    pub fn with_initial_interval(mut self, initial_interval: Duration) -> Self {
+        self.initial_interval = initial_interval;
+        self
+    }
+
+    /// Sets the upper bound for the delay between polls.
+    pub fn with_max_interval(mut self, max_interval: Duration) -> Self {
+        self.max_interval = max_interval;
+        self
+    }
+
+    /// Sets the factor applied to the delay after every poll. Values below 1 are treated as 1.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Sets how long to poll before giving up.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns how long to poll before giving up.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns the delay after poll number `poll`, starting at 1.
+    pub fn interval(&self, poll: u32) -> Duration {
+        let factor = self.multiplier.powi(poll.saturating_sub(1) as i32);
+        let interval = self.initial_interval.as_secs_f64() * factor;
+        Duration::from_secs_f64(interval.min(self.max_interval.as_secs_f64()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_grows_and_is_capped() {
+        let policy = PollPolicy::new()
+            .with_initial_interval(Duration::from_secs(2))
+            .with_multiplier(2.0)
+            .with_max_interval(Duration::from_secs(10));
+
+        assert_eq!(policy.interval(1), Duration::from_secs(2));
+        assert_eq!(policy.interval(2), Duration::from_secs(4));
+        assert_eq!(policy.interval(3), Duration::from_secs(8));
+        assert_eq!(policy.interval(4), Duration::from_secs(10));
+        assert_eq!(policy.interval(100), Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,411 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::auth::AccessToken;
+use crate::creator::CreatorData;
+use crate::error::TikTokApiError;
+use crate::media::VideoInfo;
+
+use super::{PhotoPostInfo, PostInfo, PrivacyLevel, Service};
+
+/// What is known locally about a video before it is posted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VideoMetadata {
+    /// The duration of the video, if known.
+    pub duration: Option<Duration>,
+}
+
+impl From<&VideoInfo> for VideoMetadata {
+    fn from(video: &VideoInfo) -> Self {
+        Self {
+            duration: video.duration,
+        }
+    }
+}
+
+/// A reason TikTok would reject a post for the creator's current settings.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PostViolation {
+    /// The privacy level is not among the creator's `privacy_level_options`.
+    PrivacyLevelNotAllowed {
+        requested: PrivacyLevel,
+        allowed: Vec<String>,
+    },
+    /// The creator disabled comments, so the post must set `disable_comment`.
+    CommentDisabled,
+    /// The creator disabled duets, so the post must set `disable_duet`.
+    DuetDisabled,
+    /// The creator disabled stitches, so the post must set `disable_stitch`.
+    StitchDisabled,
+    /// The video is longer than the creator may post.
+    VideoTooLong { duration: Duration, max: Duration },
+}
+
+impl fmt::Display for PostViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PostViolation::PrivacyLevelNotAllowed { requested, allowed } => write!(
+                f,
+                "privacy level {} is not allowed, expected one of [{}]",
+                requested,
+                allowed.join(", ")
+            ),
+            PostViolation::CommentDisabled => {
+                f.write_str("the creator disabled comments, set disable_comment")
+            }
+            PostViolation::DuetDisabled => {
+                f.write_str("the creator disabled duets, set disable_duet")
+            }
+            PostViolation::StitchDisabled => {
+                f.write_str("the creator disabled stitches, set disable_stitch")
+            }
+            PostViolation::VideoTooLong { duration, max } => write!(
+                f,
+                "the video lasts {:?}, longer than the creator's limit of {:?}",
+                duration, max
+            ),
+        }
+    }
+}
+
+/// Checks a video post against the creator's settings returned by
+/// `creator::Service::get_creator_info`.
+///
+/// # Arguments
+///
+/// * `creator` - The creator information.
+/// * `post_info` - The post information.
+/// * `video` - The local video metadata, if known. The duration is only checked when present.
+///
+/// # Returns
+///
+/// * `Vec<PostViolation>` - Every violation found, empty if the post is acceptable.
+pub fn validate_post(
+    creator: &CreatorData,
+    post_info: &PostInfo,
+    video: Option<&VideoMetadata>,
+) -> Vec<PostViolation> {
+    let mut violations = Vec::new();
+
+    check_privacy_level(creator, &post_info.privacy_level, &mut violations);
+    if creator.comment_disabled && !post_info.disable_comment {
+        violations.push(PostViolation::CommentDisabled);
+    }
+    if creator.duet_disabled && !post_info.disable_duet {
+        violations.push(PostViolation::DuetDisabled);
+    }
+    if creator.stitch_disabled && !post_info.disable_stitch {
+        violations.push(PostViolation::StitchDisabled);
+    }
+
+    let duration = video.and_then(|video| video.duration);
+    if let Some(duration) = duration {
+        let max = Duration::from_secs(creator.max_video_post_duration_sec.into());
+        if creator.max_video_post_duration_sec > 0 && duration > max {
+            violations.push(PostViolation::VideoTooLong { duration, max });
+        }
+    }
+
+    violations
+}
+
+/// Checks a photo post against the creator's settings returned by
+/// `creator::Service::get_creator_info`.
+///
+/// Only the privacy level, if set, and comments are checked, since duets, stitches and the
+/// duration limit do not apply to photos.
+///
+/// # Arguments
+///
+/// * `creator` - The creator information.
+/// * `post_info` - The photo post information.
+///
+/// # Returns
+///
+/// * `Vec<PostViolation>` - Every violation found, empty if the post is acceptable.
+pub fn validate_photo_post(creator: &CreatorData, post_info: &PhotoPostInfo) -> Vec<PostViolation> {
+    let mut violations = Vec::new();
+
+    if let Some(privacy_level) = &post_info.privacy_level {
+        check_privacy_level(creator, privacy_level, &mut violations);
+    }
+    if creator.comment_disabled && !post_info.disable_comment {
+        violations.push(PostViolation::CommentDisabled);
+    }
+
+    violations
+}
+
+fn check_privacy_level(
+    creator: &CreatorData,
+    privacy_level: &PrivacyLevel,
+    violations: &mut Vec<PostViolation>,
+) {
+    let requested = privacy_level.as_str();
+    if !creator
+        .privacy_level_options
+        .iter()
+        .any(|option| option == requested)
+    {
+        violations.push(PostViolation::PrivacyLevelNotAllowed {
+            requested: privacy_level.clone(),
+            allowed: creator.privacy_level_options.clone(),
+        });
+    }
+}
+
+impl Service {
+    /// Fetches the creator information for `token` and checks a video post against it.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `post_info` - The post information.
+    /// * `video` - The local video metadata, if known.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<PostViolation>, TikTokApiError>` - The violations found, or an error if the
+    ///   creator information could not be fetched.
+    pub async fn check_post(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        post_info: &PostInfo,
+        video: Option<&VideoMetadata>,
+    ) -> Result<Vec<PostViolation>, TikTokApiError> {
+        let token = token.into();
+        let creator = crate::creator::Service::from_client(self.client.clone())
+            .get_creator_info(token)
+            .await?;
+        Ok(validate_post(&creator, post_info, video))
+    }
+
+    /// Fetches the creator information for `token` and checks a photo post against it.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `post_info` - The photo post information.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<PostViolation>, TikTokApiError>` - The violations found, or an error if the
+    ///   creator information could not be fetched.
+    pub async fn check_photo_post(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        post_info: &PhotoPostInfo,
+    ) -> Result<Vec<PostViolation>, TikTokApiError> {
+        let token = token.into();
+        let creator = crate::creator::Service::from_client(self.client.clone())
+            .get_creator_info(token)
+            .await?;
+        Ok(validate_photo_post(&creator, post_info))
+    }
+
+    /// Runs `check_post` when pre-flight validation is enabled, failing with `PostRejected` if
+    /// anything is violated.
+    pub(super) async fn preflight(
+        &self,
+        token: AccessToken<'_>,
+        post_info: &PostInfo,
+        video: Option<&VideoMetadata>,
+    ) -> Result<(), TikTokApiError> {
+        if !self.preflight {
+            return Ok(());
+        }
+        rejected(self.check_post(token, post_info, video).await?)
+    }
+
+    /// Runs `check_photo_post` when pre-flight validation is enabled, failing with
+    /// `PostRejected` if anything is violated.
+    pub(super) async fn preflight_photo(
+        &self,
+        token: AccessToken<'_>,
+        post_info: &PhotoPostInfo,
+    ) -> Result<(), TikTokApiError> {
+        if !self.preflight {
+            return Ok(());
+        }
+        rejected(self.check_photo_post(token, post_info).await?)
+    }
+}
+
+fn rejected(violations: Vec<PostViolation>) -> Result<(), TikTokApiError> {
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(TikTokApiError::PostRejected(violations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::direct_post::{
+        MediaType, PhotoInitRequest, PostMode, Source, SourceInfoBuilder, VideoInitRequest,
+    };
+
+    fn creator() -> CreatorData {
+        CreatorData {
+            privacy_level_options: vec![
+                "SELF_ONLY".to_string(),
+                "MUTUAL_FOLLOW_FRIENDS".to_string(),
+            ],
+            comment_disabled: true,
+            duet_disabled: false,
+            stitch_disabled: true,
+            max_video_post_duration_sec: 60,
+            ..CreatorData::default()
+        }
+    }
+
+    fn post_info(privacy_level: PrivacyLevel) -> PostInfo {
+        PostInfo {
+            title: "title".to_string(),
+            privacy_level,
+            disable_duet: false,
+            disable_comment: false,
+            disable_stitch: true,
+            video_cover_timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_post_reports_every_violation() {
+        let video = VideoMetadata {
+            duration: Some(Duration::from_secs(90)),
+        };
+        let violations = validate_post(
+            &creator(),
+            &post_info(PrivacyLevel::PublicToEveryone),
+            Some(&video),
+        );
+
+        assert_eq!(
+            violations,
+            vec![
+                PostViolation::PrivacyLevelNotAllowed {
+                    requested: PrivacyLevel::PublicToEveryone,
+                    allowed: vec!["SELF_ONLY".to_string(), "MUTUAL_FOLLOW_FRIENDS".to_string()],
+                },
+                PostViolation::CommentDisabled,
+                PostViolation::VideoTooLong {
+                    duration: Duration::from_secs(90),
+                    max: Duration::from_secs(60),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_post_accepts_allowed_post() {
+        let mut post_info = post_info(PrivacyLevel::SelfOnly);
+        post_info.disable_comment = true;
+
+        assert!(validate_post(&creator(), &post_info, None).is_empty());
+    }
+
+    #[test]
+    fn test_validate_photo_post_checks_privacy_level_and_comments() {
+        let post_info = PhotoPostInfo {
+            privacy_level: Some(PrivacyLevel::PublicToEveryone),
+            ..PhotoPostInfo::default()
+        };
+        assert_eq!(
+            validate_photo_post(&creator(), &post_info),
+            vec![
+                PostViolation::PrivacyLevelNotAllowed {
+                    requested: PrivacyLevel::PublicToEveryone,
+                    allowed: vec!["SELF_ONLY".to_string(), "MUTUAL_FOLLOW_FRIENDS".to_string()],
+                },
+                PostViolation::CommentDisabled,
+            ]
+        );
+
+        let post_info = PhotoPostInfo {
+            disable_comment: true,
+            ..PhotoPostInfo::default()
+        };
+        assert!(validate_photo_post(&creator(), &post_info).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_preflight_rejects_before_init() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/creator_info/query/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"privacy_level_options": ["SELF_ONLY"]},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/video/init/"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let service = Service::new()
+            .with_base_url(&server.uri())
+            .with_preflight(true);
+        let request = VideoInitRequest {
+            post_info: post_info(PrivacyLevel::PublicToEveryone),
+            source_info: crate::direct_post::ChunkPlan::for_size(1024)
+                .unwrap()
+                .source_info(),
+        };
+
+        match service.post_video("token", request).await {
+            Err(TikTokApiError::PostRejected(violations)) => assert_eq!(violations.len(), 1),
+            other => panic!("expected PostRejected, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preflight_rejects_photo_before_init() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/creator_info/query/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"privacy_level_options": ["SELF_ONLY"], "comment_disabled": true},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/content/init/"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let service = Service::new()
+            .with_base_url(&server.uri())
+            .with_preflight(true);
+        let request = PhotoInitRequest::new(
+            post_info(PrivacyLevel::PublicToEveryone),
+            SourceInfoBuilder::default()
+                .source(Source::PullFromUrl)
+                .photo_cover_index(Some(0))
+                .photo_images(Some(vec!["https://example.com/0.jpg".to_string()]))
+                .build()
+                .unwrap(),
+            PostMode::DirectPost,
+            MediaType::Photo,
+        );
+
+        match service.post_photo("token", request).await {
+            Err(TikTokApiError::PostRejected(violations)) => assert_eq!(violations.len(), 2),
+            other => panic!("expected PostRejected, got {:?}", other),
+        }
+    }
+}
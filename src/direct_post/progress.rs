@@ -0,0 +1,252 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+
+use super::{Chunk, ChunkPlan, PublishStatus};
+
+/// The stage of a video post reported by `UploadProgress`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UploadStage {
+    /// Chunks are being uploaded.
+    #[default]
+    Uploading,
+    /// Every chunk has been uploaded and TikTok is processing the post.
+    Processing,
+}
+
+/// A snapshot of the progress of a video upload.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UploadProgress {
+    pub stage: UploadStage,
+    /// Bytes accepted so far, including chunks uploaded before a resumed session.
+    pub bytes_sent: u64,
+    pub total_bytes: u64,
+    pub chunks_done: u32,
+    pub total_chunks: u32,
+    /// Time since this upload call started.
+    pub elapsed: Duration,
+    /// Average upload rate of this call, in bytes per second.
+    pub bytes_per_second: f64,
+    /// Estimated time until every byte is sent, once a rate is known.
+    pub eta: Option<Duration>,
+    /// The last post status returned by TikTok during the processing stage.
+    pub post_status: Option<PublishStatus>,
+}
+
+impl UploadProgress {
+    /// Returns the uploaded fraction of the video, between 0 and 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        self.bytes_sent as f64 / self.total_bytes as f64
+    }
+}
+
+#[derive(Clone)]
+enum Sink {
+    Callback(Arc<dyn Fn(&UploadProgress) + Send + Sync>),
+    Channel(Arc<watch::Sender<UploadProgress>>),
+}
+
+/// Receives `UploadProgress` updates from a `direct_post::Service`, either through a callback
+/// or a `tokio::sync::watch` channel.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    sink: Sink,
+}
+
+impl ProgressReporter {
+    /// Creates a reporter calling `callback` with every update.
+    ///
+    /// The callback runs on the uploading task, so it should return quickly.
+    pub fn callback<F>(callback: F) -> Self
+    where
+        F: Fn(&UploadProgress) + Send + Sync + 'static,
+    {
+        Self {
+            sink: Sink::Callback(Arc::new(callback)),
+        }
+    }
+
+    /// Creates a reporter publishing every update to the returned watch receiver.
+    pub fn channel() -> (Self, watch::Receiver<UploadProgress>) {
+        let (sender, receiver) = watch::channel(UploadProgress::default());
+        let reporter = Self {
+            sink: Sink::Channel(Arc::new(sender)),
+        };
+        (reporter, receiver)
+    }
+
+    fn report(&self, progress: &UploadProgress) {
+        match &self.sink {
+            Sink::Callback(callback) => callback(progress),
+            Sink::Channel(sender) => {
+                sender.send_replace(progress.clone());
+            }
+        }
+    }
+}
+
+struct TrackerState {
+    stage: UploadStage,
+    total_bytes: u64,
+    total_chunks: u32,
+    resumed_bytes: u64,
+    committed_bytes: u64,
+    in_flight_bytes: u64,
+    chunks_done: u32,
+    post_status: Option<PublishStatus>,
+}
+
+/// Tracks the progress of one upload call and forwards it to a `ProgressReporter`.
+#[derive(Clone)]
+pub(crate) struct ProgressTracker {
+    reporter: Option<ProgressReporter>,
+    started: Instant,
+    state: Arc<Mutex<TrackerState>>,
+}
+
+impl ProgressTracker {
+    /// Creates a tracker for `plan`, where the `completed` chunks were uploaded earlier.
+    pub(crate) fn new(
+        reporter: Option<ProgressReporter>,
+        plan: &ChunkPlan,
+        completed: &[Chunk],
+    ) -> Self {
+        let resumed_bytes = completed.iter().map(Chunk::len).sum();
+        Self {
+            reporter,
+            started: Instant::now(),
+            state: Arc::new(Mutex::new(TrackerState {
+                stage: UploadStage::Uploading,
+                total_bytes: plan.video_size,
+                total_chunks: plan.total_chunk_count,
+                resumed_bytes,
+                committed_bytes: resumed_bytes,
+                in_flight_bytes: 0,
+                chunks_done: completed.len() as u32,
+                post_status: None,
+            })),
+        }
+    }
+
+    /// Discards the bytes of a failed attempt before a chunk is sent again.
+    pub(crate) fn begin_attempt(&self) {
+        self.update(|state| state.in_flight_bytes = 0);
+    }
+
+    /// Records `bytes` sent of the chunk in flight.
+    pub(crate) fn sent(&self, bytes: u64) {
+        self.update(|state| state.in_flight_bytes += bytes);
+    }
+
+    /// Records that `chunk` was accepted.
+    pub(crate) fn chunk_done(&self, chunk: Chunk) {
+        self.update(|state| {
+            state.committed_bytes += chunk.len();
+            state.in_flight_bytes = 0;
+            state.chunks_done += 1;
+        });
+    }
+
+    /// Records a post status returned while TikTok processes the post.
+    pub(crate) fn processing(&self, post_status: &PublishStatus) {
+        self.update(|state| {
+            state.stage = UploadStage::Processing;
+            state.post_status = Some(post_status.clone());
+        });
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut TrackerState)) {
+        let Some(reporter) = &self.reporter else {
+            return;
+        };
+        let progress = {
+            let mut state = self.state.lock().unwrap();
+            apply(&mut state);
+            self.snapshot(&state)
+        };
+        reporter.report(&progress);
+    }
+
+    fn snapshot(&self, state: &TrackerState) -> UploadProgress {
+        let elapsed = self.started.elapsed();
+        let bytes_sent = (state.committed_bytes + state.in_flight_bytes).min(state.total_bytes);
+        let sent_by_call = bytes_sent.saturating_sub(state.resumed_bytes);
+        let bytes_per_second = if elapsed.is_zero() {
+            0.0
+        } else {
+            sent_by_call as f64 / elapsed.as_secs_f64()
+        };
+        let eta = (bytes_per_second > 0.0).then(|| {
+            Duration::from_secs_f64((state.total_bytes - bytes_sent) as f64 / bytes_per_second)
+        });
+
+        UploadProgress {
+            stage: state.stage,
+            bytes_sent,
+            total_bytes: state.total_bytes,
+            chunks_done: state.chunks_done,
+            total_chunks: state.total_chunks,
+            elapsed,
+            bytes_per_second,
+            eta,
+            post_status: state.post_status.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_reports_bytes_chunks_and_retries() {
+        let plan = ChunkPlan::new(3000, 3000).unwrap();
+        let (reporter, receiver) = ProgressReporter::channel();
+        let tracker = ProgressTracker::new(Some(reporter), &plan, &[]);
+
+        tracker.begin_attempt();
+        tracker.sent(1000);
+        assert_eq!(receiver.borrow().bytes_sent, 1000);
+
+        // A retried chunk starts over.
+        tracker.begin_attempt();
+        tracker.sent(2000);
+        assert_eq!(receiver.borrow().bytes_sent, 2000);
+
+        tracker.sent(1000);
+        tracker.chunk_done(plan.chunk(0).unwrap());
+        let progress = receiver.borrow().clone();
+        assert_eq!(progress.bytes_sent, 3000);
+        assert_eq!(progress.chunks_done, 1);
+        assert_eq!(progress.fraction(), 1.0);
+        assert_eq!(progress.stage, UploadStage::Uploading);
+
+        tracker.processing(&PublishStatus::ProcessingUpload);
+        let progress = receiver.borrow().clone();
+        assert_eq!(progress.stage, UploadStage::Processing);
+        assert_eq!(progress.post_status, Some(PublishStatus::ProcessingUpload));
+    }
+
+    #[test]
+    fn test_resumed_bytes_count_as_sent() {
+        let plan = ChunkPlan::new(12 * 1024 * 1024, 5 * 1024 * 1024).unwrap();
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let recorded = updates.clone();
+        let reporter = ProgressReporter::callback(move |progress: &UploadProgress| {
+            recorded.lock().unwrap().push(progress.clone())
+        });
+
+        let tracker = ProgressTracker::new(Some(reporter), &plan, &[plan.chunk(0).unwrap()]);
+        tracker.begin_attempt();
+
+        let updates = updates.lock().unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].bytes_sent, 5 * 1024 * 1024);
+        assert_eq!(updates[0].chunks_done, 1);
+        assert_eq!(updates[0].eta, None);
+    }
+}
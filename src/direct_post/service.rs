@@ -1,23 +1,60 @@
+use std::io::{self, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
 use super::{
-    MediaType, PhotoInitRequest, PhotoInitRequestBuilder, PostInfo, PostMode, PostStatusData,
-    PostStatusResponse, Source, SourceInfoBuilder, VideoInitRequest, VideoInitRequestBuilder,
-    VideoInitResponse, VideoInitResponseData,
+    video_content_type, Chunk, ChunkPlan, ChunkResult, PhotoInitRequest, PhotoPost, PollPolicy,
+    PostInfo, PostMode, PostStatusData, PostStatusResponse, ProgressReporter, ProgressTracker,
+    PublishStatus, Source, SourceInfoBuilder, UploadSession, VideoInitRequest,
+    VideoInitRequestBuilder, VideoInitResponse, VideoInitResponseData, VideoMetadata,
 };
-use crate::error::{ErrorResponse, TikTokApiError};
-use reqwest::Client;
+use crate::api::{decode_envelope, decode_failure, TikTokClient};
+use crate::auth::{AccessToken, OwnedAccessToken, TikTokScope};
+use crate::error::TikTokApiError;
+use crate::media::inspect_video;
+use chrono::Utc;
+use futures::{stream, Stream, TryStreamExt};
+use reqwest::Body;
 use serde_json::json;
-use tokio::{fs::File, io::AsyncReadExt};
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
 
 /// # Please review our URL ownership verification rules at https://developers.tiktok.com/doc/content-posting-api-media-transfer-guide/#pull_from_url,
+#[derive(Clone)]
 pub struct Service {
-    base_url: String,
+    pub(super) client: TikTokClient,
+    progress: Option<ProgressReporter>,
+    pub(super) poll_policy: PollPolicy,
+    pub(super) preflight: bool,
+}
+
+impl Default for Service {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Service {
+    /// The scopes `post_video` and the direct post upload functions need.
+    pub const VIDEO_INIT_SCOPES: &'static [TikTokScope] = &[TikTokScope::VideoPublish];
+
     /// Creates a new instance of the Service.
     pub fn new() -> Self {
+        Self::from_client(TikTokClient::new())
+    }
+
+    /// Creates a new instance of the Service sharing the given `TikTokClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client whose connection pool and configuration are reused.
+    pub fn from_client(client: TikTokClient) -> Self {
         Self {
-            base_url: String::from("https://open.tiktokapis.com"),
+            client,
+            progress: None,
+            poll_policy: PollPolicy::default(),
+            preflight: false,
         }
     }
 
@@ -27,12 +64,54 @@ impl Service {
     ///
     /// * `base_url` - A string slice that holds the custom base URL.
     pub fn with_base_url(mut self, base_url: &str) -> Self {
-        self.base_url = base_url.into();
+        self.client = self.client.with_base_url(base_url);
+        self
+    }
+
+    /// Reports the progress of video uploads made by this Service to `reporter`.
+    ///
+    /// Updates are sent while chunks are uploaded and, for the simplified upload functions,
+    /// when the post status is checked afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - The callback or channel receiving `UploadProgress` updates.
+    pub fn with_progress(mut self, reporter: ProgressReporter) -> Self {
+        self.progress = Some(reporter);
+        self
+    }
+
+    /// Sets how the simplified upload functions poll the post status until it is final.
+    ///
+    /// # Arguments
+    ///
+    /// * `poll_policy` - The poll policy, `PollPolicy::default()` by default.
+    pub fn with_poll_policy(mut self, poll_policy: PollPolicy) -> Self {
+        self.poll_policy = poll_policy;
+        self
+    }
+
+    /// Enables or disables pre-flight validation of video and photo posts.
+    ///
+    /// When enabled, the creator information is fetched before every video post and every
+    /// directly posted photo post is initialized, and posts violating the creator's settings
+    /// fail with `PostRejected` instead of being sent. See `validate_post` and
+    /// `validate_photo_post` for the checks. Photos sent to the creator's inbox are not checked,
+    /// since the creator picks their settings in the app.
+    ///
+    /// # Arguments
+    ///
+    /// * `preflight` - Whether to validate posts, disabled by default.
+    pub fn with_preflight(mut self, preflight: bool) -> Self {
+        self.preflight = preflight;
         self
     }
 
     /// Initializes a video post on TikTok.
     ///
+    /// The request is only retried if the connection could not be established, since sending
+    /// it twice could create a duplicate post.
+    ///
     /// # Arguments
     ///
     /// * `token` - The API token.
@@ -43,38 +122,48 @@ impl Service {
     /// * `Result<VideoInitResponseData, TikTokApiError>` - The response data or an error.
     pub async fn post_video(
         &self,
-        token: &str,
+        token: impl Into<AccessToken<'_>>,
         video_init_request: VideoInitRequest,
     ) -> Result<VideoInitResponseData, TikTokApiError> {
-        let url = format!("{}/v2/post/publish/video/init/", self.base_url);
-        let client = Client::new();
+        let token = token.into();
+        self.init_video(token, video_init_request, None).await
+    }
 
-        let response = client
+    /// Initializes a video post after the pre-flight validation, if enabled, accepted it.
+    pub(super) async fn init_video(
+        &self,
+        token: AccessToken<'_>,
+        video_init_request: VideoInitRequest,
+        video: Option<&VideoMetadata>,
+    ) -> Result<VideoInitResponseData, TikTokApiError> {
+        token.require(Self::VIDEO_INIT_SCOPES)?;
+        self.preflight(token, &video_init_request.post_info, video)
+            .await?;
+
+        let url = self.client.url("/v2/post/publish/video/init/");
+
+        let request = self
+            .client
+            .http()
             .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
+            .header("Authorization", format!("Bearer {}", token.as_str()))
             .header("Content-Type", "application/json; charset=UTF-8")
-            .json(&video_init_request)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .json(&video_init_request);
 
-        let status = response.status();
-        let body = response
-            .text()
+        self.client
+            .execute_once(request, |status, body| {
+                decode_envelope(status, body, |video_init_response: VideoInitResponse| {
+                    video_init_response.data
+                })
+            })
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let video_init_response: VideoInitResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-
-        if status.is_success() && video_init_response.error.code == "ok" {
-            Ok(video_init_response.data)
-        } else {
-            Err(TikTokApiError::from(video_init_response.error))
-        }
     }
 
-    /// Uploads a video file to the provided upload URL.
+    /// Uploads a video file to the provided upload URL in a single request.
+    ///
+    /// The init request must declare the whole video as one chunk, i.e. `chunk_size` equal to
+    /// `video_size` and a `total_chunk_count` of 1. Use `upload_video_chunks` for videos
+    /// declared with another chunking, such as `ChunkPlan::for_size`.
     ///
     /// # Arguments
     ///
@@ -89,40 +178,227 @@ impl Service {
         upload_url: &str,
         file_path: &str,
     ) -> Result<(), TikTokApiError> {
-        let mut file = File::open(file_path)
+        let video_size = file_size(file_path).await?;
+        if video_size == 0 {
+            return Err(TikTokApiError::InvalidRequest(
+                "cannot upload an empty video".to_string(),
+            ));
+        }
+        let plan = ChunkPlan {
+            video_size,
+            chunk_size: video_size,
+            total_chunk_count: 1,
+        };
+        self.upload_video_chunks(upload_url, file_path, &plan)
             .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .map(|_| ())
+    }
 
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)
+    /// Uploads a video file to the provided upload URL in the chunks of `plan`.
+    ///
+    /// Each chunk is streamed from disk, so memory use does not grow with the size of the
+    /// video. Chunks are uploaded in order and the upload stops at the first failed chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `upload_url` - The URL to which the video file should be uploaded.
+    /// * `file_path` - The path to the video file on the local filesystem.
+    /// * `plan` - The chunk plan declared in the init request.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<ChunkResult>, TikTokApiError>` - The result of every chunk or an error.
+    pub async fn upload_video_chunks(
+        &self,
+        upload_url: &str,
+        file_path: &str,
+        plan: &ChunkPlan,
+    ) -> Result<Vec<ChunkResult>, TikTokApiError> {
+        let tracker = self.tracker(plan, &[]);
+        self.upload_chunks(upload_url, file_path, plan, &tracker)
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
+    }
 
-        let client = Client::new();
+    pub(super) async fn upload_chunks(
+        &self,
+        upload_url: &str,
+        file_path: &str,
+        plan: &ChunkPlan,
+        tracker: &ProgressTracker,
+    ) -> Result<Vec<ChunkResult>, TikTokApiError> {
+        check_file_size(file_path, plan).await?;
 
-        let response = client
-            .put(upload_url)
-            .header(
-                "Content-Range",
-                format!("bytes 0-{}/{}", buffer.len() - 1, buffer.len()),
-            )
-            .header("Content-Type", "video/mp4")
-            .body(buffer)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            let body = response
-                .text()
-                .await
-                .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-            let error_response: ErrorResponse = serde_json::from_str(&body)
-                .map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-            Err(TikTokApiError::from(error_response))
+        let mut results = Vec::with_capacity(plan.total_chunk_count as usize);
+        for chunk in plan.chunks() {
+            results.push(
+                self.upload_chunk(upload_url, file_path, plan, chunk, tracker)
+                    .await?,
+            );
+        }
+        Ok(results)
+    }
+
+    /// Initializes a `FILE_UPLOAD` video post and returns an upload session for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `post_info` - The post information.
+    /// * `file_path` - The path to the video file on the local filesystem.
+    /// * `plan` - The chunk plan to declare and upload with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<UploadSession, TikTokApiError>` - The session without uploaded chunks or an error.
+    pub async fn start_upload_session(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        post_info: PostInfo,
+        file_path: &str,
+        plan: ChunkPlan,
+    ) -> Result<UploadSession, TikTokApiError> {
+        let token = token.into();
+        check_file_size(file_path, &plan).await?;
+
+        let video_init_request = VideoInitRequestBuilder::default()
+            .post_info(post_info)
+            .source_info(plan.source_info())
+            .build()
+            .unwrap();
+        let response_data = self.post_video(token, video_init_request).await?;
+
+        Ok(UploadSession::new(
+            &response_data.publish_id,
+            &response_data.upload_url,
+            file_path,
+            plan,
+        ))
+    }
+
+    /// Uploads the chunks of `session` that have not been uploaded yet.
+    ///
+    /// Every accepted chunk is recorded in the session, and the session is written to
+    /// `save_to` after each chunk when given, so a later call can continue where this one
+    /// stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session to continue.
+    /// * `save_to` - Where to persist the session after each chunk (optional).
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<ChunkResult>, TikTokApiError>` - The results of the chunks uploaded by this
+    ///   call, or `UploadUrlExpired` if a new upload has to be initialized.
+    pub async fn resume_upload(
+        &self,
+        session: &mut UploadSession,
+        save_to: Option<&Path>,
+    ) -> Result<Vec<ChunkResult>, TikTokApiError> {
+        let tracker = self.session_tracker(session);
+        self.resume_tracked(session, save_to, &tracker).await
+    }
+
+    async fn resume_tracked(
+        &self,
+        session: &mut UploadSession,
+        save_to: Option<&Path>,
+        tracker: &ProgressTracker,
+    ) -> Result<Vec<ChunkResult>, TikTokApiError> {
+        check_file_size(&session.file_path, &session.plan).await?;
+
+        let mut results = Vec::new();
+        for chunk in session.pending_chunks() {
+            if session.is_expired(Utc::now().timestamp()) {
+                return Err(TikTokApiError::UploadUrlExpired(session.publish_id.clone()));
+            }
+
+            let result = self
+                .upload_chunk(
+                    &session.upload_url,
+                    &session.file_path,
+                    &session.plan,
+                    chunk,
+                    tracker,
+                )
+                .await?;
+            session.complete(chunk);
+            if let Some(path) = save_to {
+                session.save(path).await?;
+            }
+            results.push(result);
         }
+        Ok(results)
+    }
+
+    async fn upload_chunk(
+        &self,
+        upload_url: &str,
+        file_path: &str,
+        plan: &ChunkPlan,
+        chunk: Chunk,
+        tracker: &ProgressTracker,
+    ) -> Result<ChunkResult, TikTokApiError> {
+        self.send_chunk(
+            upload_url,
+            plan,
+            chunk,
+            video_content_type(file_path),
+            || chunk_body(PathBuf::from(file_path), chunk, tracker.clone()),
+            tracker,
+        )
+        .await
+    }
+
+    /// Uploads `chunk` with a body built by `body` for every attempt.
+    pub(super) async fn send_chunk(
+        &self,
+        upload_url: &str,
+        plan: &ChunkPlan,
+        chunk: Chunk,
+        content_type: &str,
+        body: impl Fn() -> Body,
+        tracker: &ProgressTracker,
+    ) -> Result<ChunkResult, TikTokApiError> {
+        let build = || {
+            tracker.begin_attempt();
+            self.client
+                .http()
+                .put(upload_url)
+                .header("Content-Range", chunk.content_range(plan.video_size))
+                .header("Content-Length", chunk.len())
+                .header("Content-Type", content_type)
+                .body(body())
+        };
+
+        let result = self
+            .client
+            .execute_with(build, |status, body| {
+                if status.is_success() {
+                    Ok(ChunkResult {
+                        chunk,
+                        status: status.as_u16(),
+                    })
+                } else {
+                    Err(decode_failure(status, body))
+                }
+            })
+            .await?;
+        tracker.chunk_done(chunk);
+        Ok(result)
+    }
+
+    pub(super) fn tracker(&self, plan: &ChunkPlan, completed: &[Chunk]) -> ProgressTracker {
+        ProgressTracker::new(self.progress.clone(), plan, completed)
+    }
+
+    fn session_tracker(&self, session: &UploadSession) -> ProgressTracker {
+        let completed: Vec<_> = session
+            .plan
+            .chunks()
+            .filter(|chunk| session.completed_chunks.contains(&chunk.index))
+            .collect();
+        self.tracker(&session.plan, &completed)
     }
 
     /// Retrieves the status of a post using the publish ID.
@@ -137,41 +413,132 @@ impl Service {
     /// * `Result<PostStatusData, TikTokApiError>` - The status data or an error.
     pub async fn get_post_status(
         &self,
-        token: &str,
+        token: impl Into<AccessToken<'_>>,
         publish_id: &str,
     ) -> Result<PostStatusData, TikTokApiError> {
-        let url = format!("{}/v2/post/publish/status/fetch/", self.base_url);
-        let client = Client::new();
+        let token = token.into();
+        let url = self.client.url("/v2/post/publish/status/fetch/");
 
-        let response = client
+        let request = self
+            .client
+            .http()
             .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
+            .header("Authorization", format!("Bearer {}", token.as_str()))
             .header("Content-Type", "application/json; charset=UTF-8")
-            .json(&json!({ "publish_id": publish_id }))
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .json(&json!({ "publish_id": publish_id }));
 
-        let status = response.status();
-        let body = response
-            .text()
+        self.client
+            .execute(request, |status, body| {
+                decode_envelope(status, body, |post_status_response: PostStatusResponse| {
+                    post_status_response.data
+                })
+            })
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
+    }
+
+    /// Polls the status of a post until it is `PUBLISH_COMPLETE` or `FAILED`.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `publish_id` - The ID of the post to wait for.
+    /// * `poll_policy` - The intervals between polls and the timeout.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PostStatusData, TikTokApiError>` - The final status data, or
+    ///   `PublishTimedOut` if the post is still processing when the timeout elapses.
+    pub async fn wait_for_publish(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        publish_id: &str,
+        poll_policy: PollPolicy,
+    ) -> Result<PostStatusData, TikTokApiError> {
+        let token = token.into();
+        self.wait_tracked(
+            token,
+            publish_id,
+            poll_policy,
+            PublishStatus::is_terminal,
+            None,
+        )
+        .await
+    }
+
+    /// Polls the status of a post and streams every status returned, ending after
+    /// `PUBLISH_COMPLETE`, `FAILED`, or the first error.
+    ///
+    /// The first poll is made immediately. A `PublishTimedOut` error is yielded instead of a
+    /// poll that would start after the timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `publish_id` - The ID of the post to poll.
+    /// * `poll_policy` - The intervals between polls and the timeout.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of `PostStatusData` results.
+    pub fn publish_status_stream<'a>(
+        &self,
+        token: impl Into<AccessToken<'a>>,
+        publish_id: &str,
+        poll_policy: PollPolicy,
+    ) -> impl Stream<Item = Result<PostStatusData, TikTokApiError>> {
+        let token = token.into();
+        self.status_stream(token, publish_id, poll_policy, PublishStatus::is_terminal)
+    }
 
-        let post_status_response: PostStatusResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
+    /// Streams the statuses of a post until `is_final` returns true for one of them.
+    pub(super) fn status_stream(
+        &self,
+        token: AccessToken<'_>,
+        publish_id: &str,
+        poll_policy: PollPolicy,
+        is_final: fn(&PublishStatus) -> bool,
+    ) -> impl Stream<Item = Result<PostStatusData, TikTokApiError>> {
+        let state = PollState {
+            service: self.clone(),
+            token: OwnedAccessToken::from(token),
+            publish_id: publish_id.to_string(),
+            poll_policy,
+            is_final,
+            started: Instant::now(),
+            polls: 0,
+            done: false,
+        };
+        stream::unfold(state, PollState::next)
+    }
+
+    pub(super) async fn wait_tracked(
+        &self,
+        token: AccessToken<'_>,
+        publish_id: &str,
+        poll_policy: PollPolicy,
+        is_final: fn(&PublishStatus) -> bool,
+        tracker: Option<&ProgressTracker>,
+    ) -> Result<PostStatusData, TikTokApiError> {
+        let statuses = self.status_stream(token, publish_id, poll_policy, is_final);
+        futures::pin_mut!(statuses);
 
-        if status.is_success() && post_status_response.error.code == "ok" {
-            Ok(post_status_response.data)
-        } else {
-            Err(TikTokApiError::from(post_status_response.error))
+        let mut last = None;
+        while let Some(status) = statuses.try_next().await? {
+            if let Some(tracker) = tracker {
+                tracker.processing(&status.status);
+            }
+            last = Some(status);
         }
+        last.ok_or_else(|| {
+            TikTokApiError::PublishTimedOut(publish_id.to_string(), poll_policy.timeout())
+        })
     }
 
     /// Simplified function to upload a video from a file.
     ///
     /// This function combines the steps of initializing a video post, uploading the video file,
-    /// and checking the post status into a single function call.
+    /// and waiting for the post to reach a final status into a single function call. The status
+    /// is polled according to the Service's `PollPolicy`.
     ///
     /// # Arguments
     ///
@@ -184,16 +551,18 @@ impl Service {
     ///
     /// # Returns
     ///
-    /// * `Result<PostStatusData, TikTokApiError>` - The status data or an error.
+    /// * `Result<PostStatusData, TikTokApiError>` - The status data or an error. Chunk sizes
+    ///   that break TikTok's rules are rejected with `InvalidRequest` before anything is sent.
     pub async fn upload_video_from_file(
         &self,
-        token: &str,
+        token: impl Into<AccessToken<'_>>,
         post_info: PostInfo,
         file_path: &str,
         video_size: u64,
         chunk_size: u64,
         total_chunk_count: u32,
     ) -> Result<PostStatusData, TikTokApiError> {
+        let token = token.into();
         let source_info = SourceInfoBuilder::default()
             .source(Source::FileUpload)
             .video_size(Some(video_size))
@@ -201,27 +570,154 @@ impl Service {
             .total_chunk_count(Some(total_chunk_count))
             .build()
             .unwrap();
+        let plan = ChunkPlan::from_source_info(&source_info)?;
 
         let video_init_request = VideoInitRequestBuilder::default()
             .post_info(post_info)
-            .source_info(source_info)
+            .source_info(plan.source_info())
             .build()
             .unwrap();
 
         // Call the post_video function
         let response_data = self.post_video(token, video_init_request).await?;
 
-        self.upload_video(&response_data.upload_url, file_path)
+        let tracker = self.tracker(&plan, &[]);
+        self.upload_chunks(&response_data.upload_url, file_path, &plan, &tracker)
             .await?;
 
-        // Check the post status
-        self.get_post_status(token, &response_data.publish_id).await
+        // Wait for the post to be published
+        self.wait_tracked(
+            token,
+            &response_data.publish_id,
+            self.poll_policy,
+            PublishStatus::is_terminal,
+            Some(&tracker),
+        )
+        .await
+    }
+
+    /// Simplified function to upload a video file without declaring its size.
+    ///
+    /// The file headers are read with `media::inspect_video` first, so a video breaking
+    /// TikTok's media requirements fails with `MediaRejected` before any request is sent. The
+    /// chunking follows `ChunkPlan::for_size`, and the duration is passed to the pre-flight
+    /// validation when it is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `post_info` - The post information.
+    /// * `file_path` - The path to the MP4, MOV or WebM file on the local filesystem.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PostStatusData, TikTokApiError>` - The status data or an error.
+    pub async fn upload_video_from_path(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        post_info: PostInfo,
+        file_path: &str,
+    ) -> Result<PostStatusData, TikTokApiError> {
+        let token = token.into();
+        let video = inspect_video(file_path).await?;
+        let violations = video.violations();
+        if !violations.is_empty() {
+            return Err(TikTokApiError::MediaRejected(violations));
+        }
+
+        let plan = ChunkPlan::for_size(video.size)?;
+        let video_init_request = VideoInitRequest {
+            post_info,
+            source_info: plan.source_info(),
+        };
+        let response_data = self
+            .init_video(
+                token,
+                video_init_request,
+                Some(&VideoMetadata::from(&video)),
+            )
+            .await?;
+
+        let tracker = self.tracker(&plan, &[]);
+        self.upload_chunks(&response_data.upload_url, file_path, &plan, &tracker)
+            .await?;
+
+        self.wait_tracked(
+            token,
+            &response_data.publish_id,
+            self.poll_policy,
+            PublishStatus::is_terminal,
+            Some(&tracker),
+        )
+        .await
+    }
+
+    /// Same as `upload_video_from_file`, but persists the upload session to `session_path` so an
+    /// interrupted upload continues from the last uploaded chunk when called again.
+    ///
+    /// If `session_path` holds a session for `file_path`, it is resumed instead of initializing
+    /// a new post. The session file is removed once the upload has completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The API token.
+    /// * `post_info` - The post information, used only when a new post is initialized.
+    /// * `file_path` - The path to the video file on the local filesystem.
+    /// * `session_path` - Where the upload session is persisted.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PostStatusData, TikTokApiError>` - The status data or an error. A stored
+    ///   session whose upload URL has expired yields `UploadUrlExpired`; remove the session
+    ///   file to start over.
+    pub async fn upload_video_from_file_resumable(
+        &self,
+        token: impl Into<AccessToken<'_>>,
+        post_info: PostInfo,
+        file_path: &str,
+        session_path: &Path,
+    ) -> Result<PostStatusData, TikTokApiError> {
+        let token = token.into();
+        let stored = match tokio::fs::try_exists(session_path).await {
+            Ok(true) => Some(UploadSession::load(session_path).await?),
+            Ok(false) => None,
+            Err(e) => return Err(TikTokApiError::Io(e.to_string())),
+        };
+
+        let mut session = match stored {
+            Some(session) if session.file_path == file_path => session,
+            _ => {
+                let plan = ChunkPlan::for_size(file_size(file_path).await?)?;
+                let session = self
+                    .start_upload_session(token, post_info, file_path, plan)
+                    .await?;
+                session.save(session_path).await?;
+                session
+            }
+        };
+
+        let tracker = self.session_tracker(&session);
+        self.resume_tracked(&mut session, Some(session_path), &tracker)
+            .await?;
+        tokio::fs::remove_file(session_path)
+            .await
+            .map_err(|e| TikTokApiError::Io(e.to_string()))?;
+
+        self.wait_tracked(
+            token,
+            &session.publish_id,
+            self.poll_policy,
+            PublishStatus::is_terminal,
+            Some(&tracker),
+        )
+        .await
     }
 
     /// Simplified function to upload a video from a URL.
     ///
-    /// This function combines the steps of initializing a video post and checking the post status
-    /// into a single function call.
+    /// This function combines the steps of initializing a video post and waiting for the post to
+    /// reach a final status into a single function call. The status is polled according to the
+    /// Service's `PollPolicy`.
     ///
     /// # Arguments
     ///
@@ -234,10 +730,11 @@ impl Service {
     /// * `Result<PostStatusData, TikTokApiError>` - The status data or an error.
     pub async fn upload_video_from_url(
         &self,
-        token: &str,
+        token: impl Into<AccessToken<'_>>,
         post_info: PostInfo,
         video_url: &str,
     ) -> Result<PostStatusData, TikTokApiError> {
+        let token = token.into();
         // Create SourceInfo for PULL_FROM_URL
         let source_info = SourceInfoBuilder::default()
             .source(Source::PullFromUrl)
@@ -255,12 +752,15 @@ impl Service {
         // Call the post_video function
         let response_data = self.post_video(token, video_init_request).await?;
 
-        // Check the post status
-        self.get_post_status(token, &response_data.publish_id).await
+        // Wait for the post to be published
+        self.wait_for_publish(token, &response_data.publish_id, self.poll_policy)
+            .await
     }
 
     /// Initializes a photo post on TikTok.
     ///
+    /// Direct posts are validated first if pre-flight validation is enabled.
+    ///
     /// # Arguments
     ///
     /// * `token` - The API token.
@@ -271,43 +771,43 @@ impl Service {
     /// * `Result<VideoInitResponseData, TikTokApiError>` - The response data or an error.
     pub async fn post_photo(
         &self,
-        token: &str,
+        token: impl Into<AccessToken<'_>>,
         photo_init_request: PhotoInitRequest,
     ) -> Result<VideoInitResponseData, TikTokApiError> {
-        let url = format!("{}/v2/post/publish/content/init/", self.base_url);
-        let client = Client::new();
+        let token = token.into();
+        token.require(&[photo_init_request.post_mode.scope()])?;
+        if photo_init_request.post_mode == PostMode::DirectPost {
+            self.preflight_photo(token, &photo_init_request.post_info)
+                .await?;
+        }
 
-        let response = client
+        let url = self.client.url("/v2/post/publish/content/init/");
+
+        let request = self
+            .client
+            .http()
             .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
+            .header("Authorization", format!("Bearer {}", token.as_str()))
             .header("Content-Type", "application/json; charset=UTF-8")
-            .json(&photo_init_request)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .json(&photo_init_request);
 
-        let status = response.status();
-        let body = response
-            .text()
+        self.client
+            .execute_once(request, |status, body| {
+                decode_envelope(status, body, |photo_init_response: VideoInitResponse| {
+                    photo_init_response.data
+                })
+            })
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let photo_init_response: VideoInitResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-
-        if status.is_success() && photo_init_response.error.code == "ok" {
-            Ok(photo_init_response.data)
-        } else {
-            Err(TikTokApiError::from(photo_init_response.error))
-        }
     }
 
     /// Simplified function to upload a photo from URLs.
     ///
-    /// This function combines the steps of initializing a photo post and checking the post status
-    /// into a single function call.
+    /// This function combines the steps of initializing a photo post and waiting for the post to
+    /// reach a final status into a single function call. The status is polled according to the
+    /// Service's `PollPolicy`.
     ///
-    /// The first photo will be the cover
+    /// The first photo will be the cover. Use `publish_photo_post` to choose the cover, the post
+    /// mode, or the photo-only post information.
     ///
     /// # Arguments
     ///
@@ -320,31 +820,349 @@ impl Service {
     /// * `Result<PostStatusData, TikTokApiError>` - The status data or an error.
     pub async fn upload_photo_from_urls(
         &self,
-        token: &str,
+        token: impl Into<AccessToken<'_>>,
         post_info: PostInfo,
         photo_urls: Vec<String>,
     ) -> Result<PostStatusData, TikTokApiError> {
-        // Create SourceInfo for PULL_FROM_URL
-        let source_info = SourceInfoBuilder::default()
-            .source(Source::PullFromUrl)
-            .photo_images(Some(photo_urls))
-            .photo_cover_index(Some(1)) // Assuming the first photo is the cover
-            .build()
+        let token = token.into();
+        let photo_post = PhotoPost {
+            post_info: post_info.into(),
+            photo_images: photo_urls,
+            photo_cover_index: 0,
+            post_mode: PostMode::DirectPost,
+        };
+
+        self.publish_photo_post(token, photo_post).await
+    }
+}
+
+struct PollState {
+    service: Service,
+    token: OwnedAccessToken,
+    publish_id: String,
+    poll_policy: PollPolicy,
+    is_final: fn(&PublishStatus) -> bool,
+    started: Instant,
+    polls: u32,
+    done: bool,
+}
+
+impl PollState {
+    async fn next(mut self) -> Option<(Result<PostStatusData, TikTokApiError>, Self)> {
+        if self.done {
+            return None;
+        }
+        if self.polls > 0 {
+            let delay = self.poll_policy.interval(self.polls);
+            if self.started.elapsed() + delay > self.poll_policy.timeout() {
+                self.done = true;
+                let error = TikTokApiError::PublishTimedOut(
+                    self.publish_id.clone(),
+                    self.poll_policy.timeout(),
+                );
+                return Some((Err(error), self));
+            }
+            tokio::time::sleep(delay).await;
+        }
+
+        self.polls += 1;
+        let result = self
+            .service
+            .get_post_status(self.token.as_token(), &self.publish_id)
+            .await;
+        self.done = match &result {
+            Ok(data) => (self.is_final)(&data.status),
+            Err(_) => true,
+        };
+        Some((result, self))
+    }
+}
+
+pub(super) async fn file_size(file_path: &str) -> Result<u64, TikTokApiError> {
+    tokio::fs::metadata(file_path)
+        .await
+        .map(|metadata| metadata.len())
+        .map_err(|e| TikTokApiError::Io(format!("{}: {}", file_path, e)))
+}
+
+async fn check_file_size(file_path: &str, plan: &ChunkPlan) -> Result<(), TikTokApiError> {
+    let video_size = file_size(file_path).await?;
+    if video_size != plan.video_size {
+        return Err(TikTokApiError::InvalidRequest(format!(
+            "{} is {} bytes but the chunk plan declares {} bytes",
+            file_path, video_size, plan.video_size
+        )));
+    }
+    Ok(())
+}
+
+/// Returns a body streaming the bytes of `chunk` from the file at `path`.
+///
+/// The file is only opened once the body is polled, so a new body can be built for every
+/// attempt of a chunk.
+fn chunk_body(path: PathBuf, chunk: Chunk, tracker: ProgressTracker) -> Body {
+    let stream = stream::once(async move {
+        let mut file = File::open(&path).await?;
+        file.seek(SeekFrom::Start(chunk.start)).await?;
+        Ok::<_, io::Error>(ReaderStream::new(file.take(chunk.len())))
+    })
+    .try_flatten()
+    .inspect_ok(move |bytes| tracker.sent(bytes.len() as u64));
+    Body::wrap_stream(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::direct_post::{PrivacyLevel, PublishStatus, UPLOAD_URL_TTL};
+
+    const MB: u64 = 1024 * 1024;
+
+    fn temp_video(name: &str, size: u64) -> String {
+        let path = std::env::temp_dir().join(format!("{}_{}.mp4", name, std::process::id()));
+        let bytes: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, bytes).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[tokio::test]
+    async fn test_upload_video_chunks_sends_each_range() {
+        let server = MockServer::start().await;
+        let video_size = 12 * MB + 3;
+        let plan = ChunkPlan::new(video_size, 5 * MB).unwrap();
+        for (range, status) in [
+            (format!("bytes 0-{}/{}", 5 * MB - 1, video_size), 206),
+            (
+                format!("bytes {}-{}/{}", 5 * MB, video_size - 1, video_size),
+                201,
+            ),
+        ] {
+            Mock::given(method("PUT"))
+                .and(path("/upload"))
+                .and(header("Content-Range", range.as_str()))
+                .respond_with(ResponseTemplate::new(status))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let file_path = temp_video("chunked_upload", video_size);
+        let (reporter, progress) = ProgressReporter::channel();
+        let results = Service::new()
+            .with_progress(reporter)
+            .upload_video_chunks(&format!("{}/upload", server.uri()), &file_path, &plan)
+            .await
             .unwrap();
+        std::fs::remove_file(&file_path).unwrap();
 
-        // Create PhotoInitRequest using the generated builder
-        let photo_init_request = PhotoInitRequestBuilder::default()
-            .post_info(post_info)
-            .source_info(source_info)
-            .post_mode(PostMode::DirectPost)
-            .media_type(MediaType::Photo)
-            .build()
+        let statuses: Vec<_> = results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [206, 201]);
+        assert_eq!(results[1].chunk.len(), 7 * MB + 3);
+
+        let progress = progress.borrow().clone();
+        assert_eq!(progress.bytes_sent, video_size);
+        assert_eq!(progress.chunks_done, 2);
+        assert_eq!(progress.eta, Some(Duration::ZERO));
+
+        let received = server.received_requests().await.unwrap();
+        let sizes: Vec<_> = received.iter().map(|r| r.body.len() as u64).collect();
+        assert_eq!(sizes, [5 * MB, 7 * MB + 3]);
+    }
+
+    #[tokio::test]
+    async fn test_upload_video_sends_whole_file_in_one_request() {
+        let server = MockServer::start().await;
+        let video_size = 12 * MB + 3;
+        Mock::given(method("PUT"))
+            .and(path("/upload"))
+            .and(header(
+                "Content-Range",
+                format!("bytes 0-{}/{}", video_size - 1, video_size).as_str(),
+            ))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let file_path = temp_video("single_upload", video_size);
+        Service::new()
+            .upload_video(&format!("{}/upload", server.uri()), &file_path)
+            .await
+            .unwrap();
+        std::fs::remove_file(&file_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resume_upload_skips_completed_chunks() {
+        let server = MockServer::start().await;
+        let video_size = 12 * MB;
+        let plan = ChunkPlan::new(video_size, 5 * MB).unwrap();
+        Mock::given(method("PUT"))
+            .and(path("/upload"))
+            .and(header(
+                "Content-Range",
+                format!("bytes {}-{}/{}", 5 * MB, video_size - 1, video_size).as_str(),
+            ))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let file_path = temp_video("resumed_upload", video_size);
+        let session_path =
+            std::env::temp_dir().join(format!("session_{}.json", std::process::id()));
+        let mut session = UploadSession::new(
+            "publish",
+            &format!("{}/upload", server.uri()),
+            &file_path,
+            plan,
+        );
+        session.complete(plan.chunk(0).unwrap());
+
+        let results = Service::new()
+            .resume_upload(&mut session, Some(&session_path))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(session.is_complete());
+        assert_eq!(UploadSession::load(&session_path).await.unwrap(), session);
+
+        std::fs::remove_file(&file_path).unwrap();
+        std::fs::remove_file(&session_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resume_upload_reports_expired_url() {
+        let file_path = temp_video("expired_upload", MB);
+        let plan = ChunkPlan::new(MB, MB).unwrap();
+        let mut session = UploadSession::new("publish", "http://unused", &file_path, plan);
+        session.created_at -= UPLOAD_URL_TTL.as_secs() as i64;
+
+        let result = Service::new().resume_upload(&mut session, None).await;
+        std::fs::remove_file(&file_path).unwrap();
+        assert!(matches!(result, Err(TikTokApiError::UploadUrlExpired(id)) if id == "publish"));
+    }
+
+    fn post_status(status: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {"publish_id": "publish", "status": status},
+            "error": {"code": "ok", "message": "", "log_id": "log"}
+        }))
+    }
+
+    fn fast_polls() -> PollPolicy {
+        PollPolicy::new()
+            .with_initial_interval(Duration::from_millis(1))
+            .with_max_interval(Duration::from_millis(5))
+    }
+
+    #[tokio::test]
+    async fn test_publish_status_stream_polls_until_final_status() {
+        let server = MockServer::start().await;
+        for (priority, status) in [(1, "PROCESSING_UPLOAD"), (2, "PROCESSING_UPLOAD")] {
+            Mock::given(method("POST"))
+                .and(path("/v2/post/publish/status/fetch/"))
+                .respond_with(post_status(status))
+                .up_to_n_times(1)
+                .with_priority(priority)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/status/fetch/"))
+            .respond_with(post_status("PUBLISH_COMPLETE"))
+            .with_priority(3)
+            .mount(&server)
+            .await;
+
+        let service = Service::new().with_base_url(&server.uri());
+        let statuses: Vec<PostStatusData> = service
+            .publish_status_stream("token", "publish", fast_polls())
+            .try_collect()
+            .await
+            .unwrap();
+        let statuses: Vec<_> = statuses.iter().map(|s| s.status.as_str()).collect();
+        assert_eq!(
+            statuses,
+            ["PROCESSING_UPLOAD", "PROCESSING_UPLOAD", "PUBLISH_COMPLETE"]
+        );
+
+        let status = service
+            .wait_for_publish("token", "publish", fast_polls())
+            .await
             .unwrap();
+        assert_eq!(status.status, PublishStatus::PublishComplete);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_publish_times_out() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/status/fetch/"))
+            .respond_with(post_status("PROCESSING_DOWNLOAD"))
+            .mount(&server)
+            .await;
+
+        let result = Service::new()
+            .with_base_url(&server.uri())
+            .wait_for_publish(
+                "token",
+                "publish",
+                fast_polls().with_timeout(Duration::from_millis(50)),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(TikTokApiError::PublishTimedOut(id, _)) if id == "publish"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_upload_video_from_file_rejects_invalid_chunking() {
+        let post_info = PostInfo {
+            title: "title".to_string(),
+            privacy_level: PrivacyLevel::SelfOnly,
+            disable_duet: false,
+            disable_comment: false,
+            disable_stitch: false,
+            video_cover_timestamp_ms: 0,
+        };
+
+        let result = Service::new()
+            .upload_video_from_file("token", post_info, "missing.mp4", 30 * MB, 10 * MB, 2)
+            .await;
+        assert!(matches!(result, Err(TikTokApiError::InvalidRequest(_))));
+    }
 
-        // Call the post_photo function
-        let response_data = self.post_photo(token, photo_init_request).await?;
+    #[tokio::test]
+    async fn test_upload_video_from_path_rejects_unreadable_video() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/post/publish/video/init/"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        let file_path = temp_video("not_a_video", 1024);
+        let post_info = PostInfo {
+            title: "title".to_string(),
+            privacy_level: PrivacyLevel::SelfOnly,
+            disable_duet: false,
+            disable_comment: false,
+            disable_stitch: false,
+            video_cover_timestamp_ms: 0,
+        };
 
-        // Check the post status
-        self.get_post_status(token, &response_data.publish_id).await
+        let result = Service::new()
+            .with_base_url(&server.uri())
+            .upload_video_from_path("token", post_info, &file_path)
+            .await;
+        assert!(matches!(result, Err(TikTokApiError::InvalidMedia(_))));
+        std::fs::remove_file(file_path).unwrap();
     }
 }
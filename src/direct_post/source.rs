@@ -0,0 +1,336 @@
+use std::fmt::Display;
+use std::io::{self, SeekFrom};
+
+use bytes::{Bytes, BytesMut};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use reqwest::Body;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+use crate::error::TikTokApiError;
+
+use super::{Chunk, ChunkPlan, ChunkResult, ProgressTracker, Service};
+
+/// The size of the pieces an in-memory chunk is sent in, so progress is reported while it
+/// uploads.
+const BODY_PIECE_SIZE: usize = 256 * 1024;
+
+impl Service {
+    /// Uploads a video read from `reader` to the provided upload URL in the chunks of `plan`.
+    ///
+    /// Each chunk is read into memory right before it is sent, so at most one chunk is held at
+    /// a time. The reader is seeked to the start of every chunk, so it may be at any position.
+    ///
+    /// # Arguments
+    ///
+    /// * `upload_url` - The URL to which the video should be uploaded.
+    /// * `reader` - The video, e.g. a `tokio::fs::File` or a `std::io::Cursor`.
+    /// * `plan` - The chunk plan declared in the init request, see `ChunkPlan::from_source_info`.
+    /// * `content_type` - The MIME type of the video: `video/mp4`, `video/quicktime` or
+    ///   `video/webm`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<ChunkResult>, TikTokApiError>` - The result of every chunk or an error.
+    pub async fn upload_video_from_reader<R>(
+        &self,
+        upload_url: &str,
+        mut reader: R,
+        plan: &ChunkPlan,
+        content_type: &str,
+    ) -> Result<Vec<ChunkResult>, TikTokApiError>
+    where
+        R: AsyncRead + AsyncSeek + Unpin,
+    {
+        let video_size = reader.seek(SeekFrom::End(0)).await.map_err(io_error)?;
+        check_size("the reader", video_size, plan)?;
+
+        let tracker = self.tracker(plan, &[]);
+        let mut results = Vec::with_capacity(plan.total_chunk_count as usize);
+        for chunk in plan.chunks() {
+            reader
+                .seek(SeekFrom::Start(chunk.start))
+                .await
+                .map_err(io_error)?;
+            let mut data = Vec::with_capacity(chunk.len() as usize);
+            (&mut reader)
+                .take(chunk.len())
+                .read_to_end(&mut data)
+                .await
+                .map_err(io_error)?;
+            if data.len() as u64 != chunk.len() {
+                return Err(TikTokApiError::InvalidRequest(format!(
+                    "the reader ended at byte {} of {}",
+                    chunk.start + data.len() as u64,
+                    plan.video_size
+                )));
+            }
+
+            results.push(
+                self.upload_bytes_chunk(
+                    upload_url,
+                    plan,
+                    chunk,
+                    content_type,
+                    data.into(),
+                    &tracker,
+                )
+                .await?,
+            );
+        }
+        Ok(results)
+    }
+
+    /// Uploads a video held in memory to the provided upload URL in the chunks of `plan`.
+    ///
+    /// The chunks share the memory of `video`, so nothing is copied.
+    ///
+    /// # Arguments
+    ///
+    /// * `upload_url` - The URL to which the video should be uploaded.
+    /// * `video` - The video.
+    /// * `plan` - The chunk plan declared in the init request.
+    /// * `content_type` - The MIME type of the video.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<ChunkResult>, TikTokApiError>` - The result of every chunk or an error.
+    pub async fn upload_video_from_bytes(
+        &self,
+        upload_url: &str,
+        video: Bytes,
+        plan: &ChunkPlan,
+        content_type: &str,
+    ) -> Result<Vec<ChunkResult>, TikTokApiError> {
+        check_size("the video", video.len() as u64, plan)?;
+
+        let tracker = self.tracker(plan, &[]);
+        let mut results = Vec::with_capacity(plan.total_chunk_count as usize);
+        for chunk in plan.chunks() {
+            let data = video.slice(chunk.start as usize..=chunk.end as usize);
+            results.push(
+                self.upload_bytes_chunk(upload_url, plan, chunk, content_type, data, &tracker)
+                    .await?,
+            );
+        }
+        Ok(results)
+    }
+
+    /// Uploads a video arriving as a byte stream, e.g. an S3 object or an HTTP download, to the
+    /// provided upload URL in the chunks of `plan`.
+    ///
+    /// Bytes are buffered until a chunk is complete, so at most one chunk is held in memory.
+    /// The stream must yield exactly `plan.video_size` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `upload_url` - The URL to which the video should be uploaded.
+    /// * `video` - The stream of video bytes.
+    /// * `plan` - The chunk plan declared in the init request.
+    /// * `content_type` - The MIME type of the video.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<ChunkResult>, TikTokApiError>` - The result of every chunk or an error.
+    ///   A stream error is returned as `Io`.
+    pub async fn upload_video_from_stream<S, E>(
+        &self,
+        upload_url: &str,
+        video: S,
+        plan: &ChunkPlan,
+        content_type: &str,
+    ) -> Result<Vec<ChunkResult>, TikTokApiError>
+    where
+        S: Stream<Item = Result<Bytes, E>>,
+        E: Display,
+    {
+        let video = video.map_err(|e| TikTokApiError::Io(e.to_string()));
+        futures::pin_mut!(video);
+
+        let tracker = self.tracker(plan, &[]);
+        let mut buffer = BytesMut::new();
+        let mut results = Vec::with_capacity(plan.total_chunk_count as usize);
+        for chunk in plan.chunks() {
+            while (buffer.len() as u64) < chunk.len() {
+                match video.try_next().await? {
+                    Some(bytes) => buffer.extend_from_slice(&bytes),
+                    None => {
+                        return Err(TikTokApiError::InvalidRequest(format!(
+                            "the stream ended at byte {} of {}",
+                            chunk.start + buffer.len() as u64,
+                            plan.video_size
+                        )))
+                    }
+                }
+            }
+            let data = buffer.split_to(chunk.len() as usize).freeze();
+            results.push(
+                self.upload_bytes_chunk(upload_url, plan, chunk, content_type, data, &tracker)
+                    .await?,
+            );
+        }
+
+        while buffer.is_empty() {
+            match video.try_next().await? {
+                Some(bytes) => buffer.extend_from_slice(&bytes),
+                None => return Ok(results),
+            }
+        }
+        Err(TikTokApiError::InvalidRequest(format!(
+            "the stream is longer than the {} bytes declared",
+            plan.video_size
+        )))
+    }
+
+    async fn upload_bytes_chunk(
+        &self,
+        upload_url: &str,
+        plan: &ChunkPlan,
+        chunk: Chunk,
+        content_type: &str,
+        data: Bytes,
+        tracker: &ProgressTracker,
+    ) -> Result<ChunkResult, TikTokApiError> {
+        self.send_chunk(
+            upload_url,
+            plan,
+            chunk,
+            content_type,
+            || bytes_body(data.clone(), tracker.clone()),
+            tracker,
+        )
+        .await
+    }
+}
+
+fn check_size(source: &str, video_size: u64, plan: &ChunkPlan) -> Result<(), TikTokApiError> {
+    if video_size != plan.video_size {
+        return Err(TikTokApiError::InvalidRequest(format!(
+            "{} has {} bytes but the chunk plan declares {} bytes",
+            source, video_size, plan.video_size
+        )));
+    }
+    Ok(())
+}
+
+fn io_error(error: io::Error) -> TikTokApiError {
+    TikTokApiError::Io(error.to_string())
+}
+
+/// Returns a body sending `data` in pieces, reporting each piece to `tracker`.
+fn bytes_body(data: Bytes, tracker: ProgressTracker) -> Body {
+    let pieces = (0..data.len())
+        .step_by(BODY_PIECE_SIZE)
+        .map(move |start| data.slice(start..(start + BODY_PIECE_SIZE).min(data.len())));
+    let stream = stream::iter(pieces)
+        .map(Ok::<_, io::Error>)
+        .inspect_ok(move |bytes| tracker.sent(bytes.len() as u64));
+    Body::wrap_stream(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use wiremock::matchers::{body_bytes, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn video(size: u64) -> Vec<u8> {
+        (0..size).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// Mocks one PUT per chunk of `plan`, each expecting the bytes of its range.
+    async fn mock_chunks(server: &MockServer, plan: &ChunkPlan, video: &[u8]) {
+        for chunk in plan.chunks() {
+            Mock::given(method("PUT"))
+                .and(path("/upload"))
+                .and(header(
+                    "Content-Range",
+                    chunk.content_range(plan.video_size).as_str(),
+                ))
+                .and(header("Content-Type", "video/webm"))
+                .and(body_bytes(
+                    video[chunk.start as usize..=chunk.end as usize].to_vec(),
+                ))
+                .respond_with(ResponseTemplate::new(201))
+                .expect(1)
+                .mount(server)
+                .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_video_from_reader_and_bytes() {
+        let server = MockServer::start().await;
+        let data = video(12 * MB + 3);
+        let plan = ChunkPlan::new(data.len() as u64, 5 * MB).unwrap();
+        let upload_url = format!("{}/upload", server.uri());
+        mock_chunks(&server, &plan, &data).await;
+
+        let service = Service::new();
+        let mut reader = Cursor::new(data.clone());
+        reader.set_position(7);
+        let results = service
+            .upload_video_from_reader(&upload_url, reader, &plan, "video/webm")
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        server.reset().await;
+        mock_chunks(&server, &plan, &data).await;
+        let results = service
+            .upload_video_from_bytes(&upload_url, Bytes::from(data), &plan, "video/webm")
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_upload_video_from_stream_rebuffers_pieces() {
+        let server = MockServer::start().await;
+        let data = video(12 * MB + 3);
+        let plan = ChunkPlan::new(data.len() as u64, 5 * MB).unwrap();
+        mock_chunks(&server, &plan, &data).await;
+
+        // Pieces that do not line up with the chunk boundaries.
+        let pieces: Vec<Result<Bytes, io::Error>> = data
+            .chunks(3 * MB as usize + 1)
+            .map(|piece| Ok(Bytes::copy_from_slice(piece)))
+            .collect();
+        let results = Service::new()
+            .upload_video_from_stream(
+                &format!("{}/upload", server.uri()),
+                stream::iter(pieces),
+                &plan,
+                "video/webm",
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_upload_video_from_stream_checks_length() {
+        let plan = ChunkPlan::new(1024, 1024).unwrap();
+        let service = Service::new();
+
+        let short = stream::iter(vec![Ok::<_, io::Error>(Bytes::from(video(1000)))]);
+        let result = service
+            .upload_video_from_stream("http://127.0.0.1:9/upload", short, &plan, "video/mp4")
+            .await;
+        assert!(matches!(result, Err(TikTokApiError::InvalidRequest(_))));
+
+        let result = service
+            .upload_video_from_bytes(
+                "http://127.0.0.1:9/upload",
+                Bytes::from(video(1000)),
+                &plan,
+                "video/mp4",
+            )
+            .await;
+        assert!(matches!(result, Err(TikTokApiError::InvalidRequest(_))));
+    }
+}
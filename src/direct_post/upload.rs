@@ -0,0 +1,395 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::write_private;
+use crate::error::{ApiErrorDetails, TikTokApiError};
+
+use super::{Source, SourceInfo};
+
+/// The smallest chunk size TikTok accepts, except for videos uploaded as a single chunk.
+pub const MIN_CHUNK_SIZE: u64 = 5 * 1024 * 1024;
+
+/// The largest chunk size TikTok accepts. The final chunk may exceed it by up to one chunk.
+pub const MAX_CHUNK_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The chunk size used when the caller does not choose one.
+pub const DEFAULT_CHUNK_SIZE: u64 = 10 * 1024 * 1024;
+
+/// The largest number of chunks TikTok accepts for one upload.
+pub const MAX_CHUNK_COUNT: u32 = 1000;
+
+/// How long an `upload_url` returned by TikTok stays valid.
+pub const UPLOAD_URL_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// How a video is split into chunks for a `FILE_UPLOAD`.
+///
+/// TikTok requires every chunk but the last to be `chunk_size` bytes, with `chunk_size` between
+/// 5MB and 64MB. The number of chunks is `video_size / chunk_size` rounded down, so the final
+/// chunk absorbs the remaining bytes. Videos smaller than 5MB are uploaded as one chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkPlan {
+    pub video_size: u64,
+    pub chunk_size: u64,
+    pub total_chunk_count: u32,
+}
+
+impl ChunkPlan {
+    /// Creates a plan for a video of `video_size` bytes with the given `chunk_size`.
+    ///
+    /// # Arguments
+    ///
+    /// * `video_size` - The size of the video in bytes.
+    /// * `chunk_size` - The size of each chunk in bytes. Ignored for videos smaller than 5MB.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ChunkPlan, TikTokApiError>` - The plan, or `InvalidRequest` if the sizes
+    ///   break TikTok's chunking rules.
+    pub fn new(video_size: u64, chunk_size: u64) -> Result<Self, TikTokApiError> {
+        if video_size == 0 {
+            return Err(TikTokApiError::InvalidRequest(
+                "cannot upload an empty video".to_string(),
+            ));
+        }
+        if video_size < MIN_CHUNK_SIZE {
+            return Ok(Self {
+                video_size,
+                chunk_size: video_size,
+                total_chunk_count: 1,
+            });
+        }
+        if !(MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+            return Err(TikTokApiError::InvalidRequest(format!(
+                "chunk size {} is outside {}..={} bytes",
+                chunk_size, MIN_CHUNK_SIZE, MAX_CHUNK_SIZE
+            )));
+        }
+
+        let chunk_size = chunk_size.min(video_size);
+        let total_chunk_count = video_size / chunk_size;
+        if total_chunk_count > MAX_CHUNK_COUNT as u64 {
+            return Err(TikTokApiError::InvalidRequest(format!(
+                "{} chunks of {} bytes exceed the limit of {} chunks",
+                total_chunk_count, chunk_size, MAX_CHUNK_COUNT
+            )));
+        }
+
+        Ok(Self {
+            video_size,
+            chunk_size,
+            total_chunk_count: total_chunk_count as u32,
+        })
+    }
+
+    /// Creates a plan for a video of `video_size` bytes using `DEFAULT_CHUNK_SIZE`, or larger
+    /// chunks if the video would otherwise need more than `MAX_CHUNK_COUNT` chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `video_size` - The size of the video in bytes.
+    pub fn for_size(video_size: u64) -> Result<Self, TikTokApiError> {
+        let chunk_size = video_size
+            .div_ceil(MAX_CHUNK_COUNT as u64)
+            .clamp(DEFAULT_CHUNK_SIZE, MAX_CHUNK_SIZE);
+        Self::new(video_size, chunk_size)
+    }
+
+    /// Returns the chunk at `index`, or `None` if the plan has no such chunk.
+    pub fn chunk(&self, index: u32) -> Option<Chunk> {
+        if index >= self.total_chunk_count {
+            return None;
+        }
+        let start = index as u64 * self.chunk_size;
+        let end = if index + 1 == self.total_chunk_count {
+            self.video_size - 1
+        } else {
+            start + self.chunk_size - 1
+        };
+        Some(Chunk { index, start, end })
+    }
+
+    /// Returns every chunk of the plan in upload order.
+    pub fn chunks(&self) -> impl Iterator<Item = Chunk> + '_ {
+        (0..self.total_chunk_count).filter_map(|index| self.chunk(index))
+    }
+
+    /// Recreates the plan declared by a `FILE_UPLOAD` source info.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_info` - The source info sent in the init request.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ChunkPlan, TikTokApiError>` - The plan, or `InvalidRequest` if the source info
+    ///   lacks a size or declares a chunking that breaks TikTok's rules.
+    pub fn from_source_info(source_info: &SourceInfo) -> Result<Self, TikTokApiError> {
+        let (Some(video_size), Some(chunk_size), Some(total_chunk_count)) = (
+            source_info.video_size,
+            source_info.chunk_size,
+            source_info.total_chunk_count,
+        ) else {
+            return Err(TikTokApiError::InvalidRequest(
+                "the source info does not declare video_size, chunk_size and total_chunk_count"
+                    .to_string(),
+            ));
+        };
+
+        let plan = Self::new(video_size, chunk_size)?;
+        if plan.chunk_size != chunk_size || plan.total_chunk_count != total_chunk_count {
+            return Err(TikTokApiError::InvalidRequest(format!(
+                "a {} byte video uploads as {} chunks of {} bytes, not {} chunks of {} bytes",
+                video_size, plan.total_chunk_count, plan.chunk_size, total_chunk_count, chunk_size
+            )));
+        }
+        Ok(plan)
+    }
+
+    /// Returns the `FILE_UPLOAD` source info declaring this plan to TikTok.
+    pub fn source_info(&self) -> SourceInfo {
+        SourceInfo {
+            source: Source::FileUpload,
+            video_size: Some(self.video_size),
+            chunk_size: Some(self.chunk_size),
+            total_chunk_count: Some(self.total_chunk_count),
+            video_url: None,
+            photo_cover_index: None,
+            photo_images: None,
+        }
+    }
+}
+
+/// A byte range of the video, both ends inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chunk {
+    pub index: u32,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Chunk {
+    /// Returns the number of bytes in the chunk.
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Always false, chunks contain at least one byte.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the `Content-Range` header value of the chunk.
+    pub fn content_range(&self, video_size: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, video_size)
+    }
+}
+
+/// The outcome of uploading one chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkResult {
+    pub chunk: Chunk,
+    /// The HTTP status TikTok answered with, 206 for partial content and 201 once the
+    /// upload is complete.
+    pub status: u16,
+}
+
+/// The state of a chunked upload, persisted so an interrupted upload can be resumed.
+///
+/// A session records the upload URL and chunk plan returned by the init request and the
+/// chunks TikTok has already accepted. Resuming it uploads only the remaining chunks, as long
+/// as the upload URL has not expired.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadSession {
+    pub publish_id: String,
+    pub upload_url: String,
+    pub file_path: String,
+    pub plan: ChunkPlan,
+    pub completed_chunks: BTreeSet<u32>,
+    /// Unix timestamp in seconds at which the upload URL was issued.
+    pub created_at: i64,
+}
+
+impl UploadSession {
+    /// Creates a session without completed chunks for an upload URL issued now.
+    ///
+    /// # Arguments
+    ///
+    /// * `publish_id` - The publish ID returned by the init request.
+    /// * `upload_url` - The upload URL returned by the init request.
+    /// * `file_path` - The path to the video file on the local filesystem.
+    /// * `plan` - The chunk plan declared in the init request.
+    pub fn new(publish_id: &str, upload_url: &str, file_path: &str, plan: ChunkPlan) -> Self {
+        Self {
+            publish_id: publish_id.to_string(),
+            upload_url: upload_url.to_string(),
+            file_path: file_path.to_string(),
+            plan,
+            completed_chunks: BTreeSet::new(),
+            created_at: Utc::now().timestamp(),
+        }
+    }
+
+    /// Returns the chunks that have not been uploaded yet, in upload order.
+    pub fn pending_chunks(&self) -> Vec<Chunk> {
+        self.plan
+            .chunks()
+            .filter(|chunk| !self.completed_chunks.contains(&chunk.index))
+            .collect()
+    }
+
+    /// Returns true once every chunk has been uploaded.
+    pub fn is_complete(&self) -> bool {
+        self.pending_chunks().is_empty()
+    }
+
+    /// Returns true if the upload URL is older than `UPLOAD_URL_TTL` at `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        now - self.created_at >= UPLOAD_URL_TTL.as_secs() as i64
+    }
+
+    /// Records that `chunk` has been uploaded.
+    pub fn complete(&mut self, chunk: Chunk) {
+        self.completed_chunks.insert(chunk.index);
+    }
+
+    /// Writes the session as JSON to `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write. It is replaced through a temporary file, synced to disk
+    ///   first so a crash cannot leave a truncated session behind.
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<(), TikTokApiError> {
+        let path = path.as_ref();
+        let bytes =
+            serde_json::to_vec_pretty(self).map_err(|e| TikTokApiError::Io(e.to_string()))?;
+        let tmp_path = path.with_extension("tmp");
+        write_private(&tmp_path, &bytes)
+            .await
+            .map_err(|e| TikTokApiError::Io(format!("{}: {}", tmp_path.display(), e)))?;
+        tokio::fs::rename(&tmp_path, path)
+            .await
+            .map_err(|e| TikTokApiError::Io(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Reads a session previously written with `save`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read.
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self, TikTokApiError> {
+        let path = path.as_ref();
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| TikTokApiError::Io(format!("{}: {}", path.display(), e)))?;
+        serde_json::from_slice(&bytes).map_err(|e| {
+            TikTokApiError::ParseFailed(Box::new(ApiErrorDetails {
+                message: format!("{}: {}", path.display(), e),
+                ..ApiErrorDetails::default()
+            }))
+        })
+    }
+}
+
+/// Returns the `Content-Type` of a video file based on its extension.
+pub(crate) fn video_content_type(file_path: &str) -> &'static str {
+    let extension = std::path::Path::new(file_path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("mov") => "video/quicktime",
+        Some("webm") => "video/webm",
+        _ => "video/mp4",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_small_video_is_a_single_chunk() {
+        let plan = ChunkPlan::new(3 * MB, 10 * MB).unwrap();
+        assert_eq!(plan.chunk_size, 3 * MB);
+        assert_eq!(plan.total_chunk_count, 1);
+        assert_eq!(
+            plan.chunk(0).unwrap().content_range(plan.video_size),
+            format!("bytes 0-{}/{}", 3 * MB - 1, 3 * MB)
+        );
+    }
+
+    #[test]
+    fn test_final_chunk_absorbs_remainder() {
+        let plan = ChunkPlan::new(25 * MB + 7, 10 * MB).unwrap();
+        assert_eq!(plan.total_chunk_count, 2);
+
+        let chunks: Vec<_> = plan.chunks().collect();
+        assert_eq!(chunks[0].len(), 10 * MB);
+        assert_eq!(chunks[1].start, 10 * MB);
+        assert_eq!(chunks[1].len(), 15 * MB + 7);
+        assert_eq!(chunks[1].end, plan.video_size - 1);
+        assert_eq!(plan.chunk(2), None);
+    }
+
+    #[test]
+    fn test_invalid_plans_are_rejected() {
+        assert!(ChunkPlan::new(0, 10 * MB).is_err());
+        assert!(ChunkPlan::new(100 * MB, 4 * MB).is_err());
+        assert!(ChunkPlan::new(100 * MB, 65 * MB).is_err());
+        assert!(ChunkPlan::new(6000 * MB, 5 * MB).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_upload_session_round_trip() {
+        let plan = ChunkPlan::new(25 * MB, 10 * MB).unwrap();
+        let mut session = UploadSession::new("publish", "https://upload", "video.mp4", plan);
+        assert_eq!(session.pending_chunks().len(), 2);
+
+        session.complete(plan.chunk(0).unwrap());
+        assert_eq!(session.pending_chunks(), vec![plan.chunk(1).unwrap()]);
+        assert!(!session.is_complete());
+        assert!(!session.is_expired(session.created_at + 60));
+        assert!(session.is_expired(session.created_at + 3600));
+
+        let path = std::env::temp_dir().join(format!("upload_session_{}.json", std::process::id()));
+        session.save(&path).await.unwrap();
+        assert_eq!(UploadSession::load(&path).await.unwrap(), session);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_default_plan_grows_chunks_for_large_videos() {
+        let plan = ChunkPlan::for_size(50 * MB).unwrap();
+        assert_eq!(plan.chunk_size, DEFAULT_CHUNK_SIZE);
+        assert_eq!(plan.total_chunk_count, 5);
+
+        let plan = ChunkPlan::for_size(20_000 * MB).unwrap();
+        assert_eq!(plan.chunk_size, 20 * MB);
+        assert_eq!(plan.total_chunk_count, MAX_CHUNK_COUNT);
+
+        let plan = ChunkPlan::for_size(7 * MB).unwrap();
+        assert_eq!(plan.chunk_size, 7 * MB);
+        assert_eq!(plan.total_chunk_count, 1);
+    }
+
+    #[test]
+    fn test_plan_from_source_info() {
+        let plan = ChunkPlan::new(25 * MB + 7, 10 * MB).unwrap();
+        assert_eq!(
+            ChunkPlan::from_source_info(&plan.source_info()).unwrap(),
+            plan
+        );
+
+        let mut source_info = plan.source_info();
+        source_info.total_chunk_count = Some(3);
+        assert!(ChunkPlan::from_source_info(&source_info).is_err());
+        source_info.video_size = None;
+        assert!(ChunkPlan::from_source_info(&source_info).is_err());
+    }
+}
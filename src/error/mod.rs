@@ -1,6 +1,13 @@
+use std::fmt;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::auth::TikTokScope;
+use crate::direct_post::PostViolation;
+use crate::media::MediaViolation;
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ErrorResponse {
     pub code: String,
@@ -10,26 +17,70 @@ pub struct ErrorResponse {
 
 #[derive(Error, Debug)]
 pub enum TikTokApiError {
-    #[error("Access token is invalid or not found in the request. Please refresh the token and retry. Log ID: {0}")]
-    AccessTokenInvalid(String),
+    #[error("Access token is invalid or not found in the request. Please refresh the token and retry. {0}")]
+    AccessTokenInvalid(Box<ApiErrorDetails>),
+
+    #[error("This is the generic error code for TikTok internal errors. Please refer to the error message for details and notify TikTok support. {0}")]
+    InternalError(Box<ApiErrorDetails>),
+
+    #[error("The uploaded file does not meet API specifications. Please correct the file and try again. {0}")]
+    InvalidFileUpload(Box<ApiErrorDetails>),
+
+    #[error("One or more fields in request is invalid. Please refer to the error message for details. {0}")]
+    InvalidParams(Box<ApiErrorDetails>),
+
+    #[error("The API rate limit was exceeded. Please try again later. {0}")]
+    RateLimitExceeded(Box<ApiErrorDetails>),
+
+    #[error("The user did not authorize the scope required for completing this request. Please ask the user to authorize and then retry. {0}")]
+    ScopeNotAuthorized(Box<ApiErrorDetails>),
+
+    #[error("Access token is invalid, some fields need additional scopes. Please refer to the error message for more details. {0}")]
+    ScopePermissionMissed(Box<ApiErrorDetails>),
+
+    #[error("The access token lacks the scopes required for this request. Please ask the user to authorize: {}", describe_scopes(.0))]
+    MissingScopes(Vec<TikTokScope>),
 
-    #[error("This is the generic error code for TikTok internal errors. Please refer to the error message for details and notify TikTok support. Log ID: {0}")]
-    InternalError(String),
+    #[error("Client-side rate limit reached for {0}. Retry in {1:?}")]
+    LocalRateLimited(String, Duration),
 
-    #[error("The uploaded file does not meet API specifications. Please correct the file and try again. Log ID: {0}")]
-    InvalidFileUpload(String),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
 
-    #[error("One or more fields in request is invalid. Please refer to the error message for details. Log ID: {0}")]
-    InvalidParams(String),
+    #[error("Failed to build HTTP client: {0}")]
+    ClientBuildFailed(String),
 
-    #[error("The API rate limit was exceeded. Please try again later. Log ID: {0}")]
-    RateLimitExceeded(String),
+    #[error("No token stored for open_id {0}")]
+    TokenNotFound(String),
 
-    #[error("The user did not authorize the scope required for completing this request. Please ask the user to authorize and then retry. Log ID: {0}")]
-    ScopeNotAuthorized(String),
+    #[error(
+        "The refresh token for open_id {0} has expired. Please ask the user to authorize again"
+    )]
+    RefreshTokenExpired(String),
 
-    #[error("Access token is invalid, some fields need additional scopes. Please refer to the error message for more details. Log ID: {0}")]
-    ScopePermissionMissed(String),
+    #[error("Token store failed: {0}")]
+    TokenStoreFailed(String),
+
+    #[error("The upload URL of publish ID {0} has expired. Please initialize a new upload")]
+    UploadUrlExpired(String),
+
+    #[error("Post {0} did not reach a final status within {1:?}")]
+    PublishTimedOut(String, Duration),
+
+    #[error("Post rejected by pre-flight validation: {}", describe_violations(.0))]
+    PostRejected(Vec<PostViolation>),
+
+    #[error("Invalid media: {0}")]
+    InvalidMedia(String),
+
+    #[error("Video does not meet TikTok's media requirements: {}", describe_violations(.0))]
+    MediaRejected(Vec<MediaViolation>),
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    #[error("Could not connect to TikTok: {0}")]
+    ConnectFailed(String),
 
     #[error("Request failed: {0}")]
     RequestFailed(String),
@@ -37,28 +88,251 @@ pub enum TikTokApiError {
     #[error("Response read failed: {0}")]
     ResponseReadFailed(String),
 
-    #[error("Parse failed: {0}")]
-    ParseFailed(String),
+    #[error("TikTok returned HTTP {} with an empty body{}", .0.status, describe_request(.0))]
+    EmptyResponse(Box<ApiErrorDetails>),
+
+    #[error("TikTok returned HTTP {} with a body that is not JSON{}: {}", .0.status, describe_request(.0), .0.message)]
+    NonJsonResponse(Box<ApiErrorDetails>),
+
+    #[error("Parse failed{}: {}", describe_request(.0), .0.message)]
+    ParseFailed(Box<ApiErrorDetails>),
 
-    #[error("API error: {0}, Description: {1}, Log ID: {2}")]
-    ApiError(String, String, String),
+    #[error("API error: {}. {0}", .0.code)]
+    ApiError(Box<ApiErrorDetails>),
 
-    #[error("Unknown error occurred. Code: {0}, Message: {1}, Log ID: {2}")]
-    Unknown(String, String, String),
+    #[error("Unknown error occurred. Code: {}. {0}", .0.code)]
+    Unknown(Box<ApiErrorDetails>),
+}
+
+/// Everything known about an error returned by the TikTok API. Boxed in `TikTokApiError` to
+/// keep results small.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApiErrorDetails {
+    /// The HTTP status of the response, 0 if the error did not come from a response.
+    pub status: u16,
+    /// TikTok's error code, e.g. `invalid_params`.
+    pub code: String,
+    /// TikTok's description of the error, which often names the offending field.
+    pub message: String,
+    /// The ID to quote when contacting TikTok support.
+    pub log_id: String,
+    /// The HTTP method of the request, empty if unknown.
+    pub method: String,
+    /// The path of the request, e.g. `/v2/user/info/`, empty if unknown.
+    pub path: String,
+}
+
+impl fmt::Display for ApiErrorDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.message.is_empty() {
+            write!(f, "Message: {}. ", self.message)?;
+        }
+        if !self.method.is_empty() {
+            write!(
+                f,
+                "{} {} returned HTTP {}. ",
+                self.method, self.path, self.status
+            )?;
+        }
+        write!(f, "Log ID: {}", self.log_id)
+    }
+}
+
+impl From<ErrorResponse> for ApiErrorDetails {
+    fn from(error: ErrorResponse) -> Self {
+        Self {
+            code: error.code,
+            message: error.message,
+            log_id: error.log_id,
+            ..Self::default()
+        }
+    }
 }
 
 // Function to map error response to custom error
 impl From<ErrorResponse> for TikTokApiError {
     fn from(error: ErrorResponse) -> Self {
-        match error.code.as_str() {
-            "access_token_invalid" => TikTokApiError::AccessTokenInvalid(error.log_id),
-            "internal_error" => TikTokApiError::InternalError(error.log_id),
-            "invalid_file_upload" => TikTokApiError::InvalidFileUpload(error.log_id),
-            "invalid_params" => TikTokApiError::InvalidParams(error.log_id),
-            "rate_limit_exceeded" => TikTokApiError::RateLimitExceeded(error.log_id),
-            "scope_not_authorized" => TikTokApiError::ScopeNotAuthorized(error.log_id),
-            "scope_permission_missed" => TikTokApiError::ScopePermissionMissed(error.log_id),
-            _ => TikTokApiError::Unknown(error.code, error.message, error.log_id),
+        let details = Box::new(ApiErrorDetails::from(error));
+        match details.code.as_str() {
+            "access_token_invalid" => TikTokApiError::AccessTokenInvalid(details),
+            "internal_error" => TikTokApiError::InternalError(details),
+            "invalid_file_upload" => TikTokApiError::InvalidFileUpload(details),
+            "invalid_params" => TikTokApiError::InvalidParams(details),
+            "rate_limit_exceeded" => TikTokApiError::RateLimitExceeded(details),
+            "scope_not_authorized" => TikTokApiError::ScopeNotAuthorized(details),
+            "scope_permission_missed" => TikTokApiError::ScopePermissionMissed(details),
+            _ => TikTokApiError::Unknown(details),
         }
     }
 }
+
+impl TikTokApiError {
+    /// Returns the details of an error returned by the TikTok API, or of a response that could
+    /// not be read as one.
+    pub fn details(&self) -> Option<&ApiErrorDetails> {
+        match self {
+            TikTokApiError::AccessTokenInvalid(details)
+            | TikTokApiError::InternalError(details)
+            | TikTokApiError::InvalidFileUpload(details)
+            | TikTokApiError::InvalidParams(details)
+            | TikTokApiError::RateLimitExceeded(details)
+            | TikTokApiError::ScopeNotAuthorized(details)
+            | TikTokApiError::ScopePermissionMissed(details)
+            | TikTokApiError::EmptyResponse(details)
+            | TikTokApiError::NonJsonResponse(details)
+            | TikTokApiError::ParseFailed(details)
+            | TikTokApiError::ApiError(details)
+            | TikTokApiError::Unknown(details) => Some(details),
+            _ => None,
+        }
+    }
+
+    fn details_mut(&mut self) -> Option<&mut ApiErrorDetails> {
+        match self {
+            TikTokApiError::AccessTokenInvalid(details)
+            | TikTokApiError::InternalError(details)
+            | TikTokApiError::InvalidFileUpload(details)
+            | TikTokApiError::InvalidParams(details)
+            | TikTokApiError::RateLimitExceeded(details)
+            | TikTokApiError::ScopeNotAuthorized(details)
+            | TikTokApiError::ScopePermissionMissed(details)
+            | TikTokApiError::EmptyResponse(details)
+            | TikTokApiError::NonJsonResponse(details)
+            | TikTokApiError::ParseFailed(details)
+            | TikTokApiError::ApiError(details)
+            | TikTokApiError::Unknown(details) => Some(details),
+            _ => None,
+        }
+    }
+
+    /// Returns the log ID of an error returned by the TikTok API, if TikTok sent one.
+    pub fn log_id(&self) -> Option<&str> {
+        self.details()
+            .map(|details| details.log_id.as_str())
+            .filter(|log_id| !log_id.is_empty())
+    }
+
+    /// Returns true if sending the same request again may succeed: rate limiting, TikTok
+    /// internal errors, transport failures, and unknown or unreadable responses with a 429 or
+    /// 5xx status, such as a gateway's HTML error page.
+    ///
+    /// This is the default retry classifier, but it only applies to requests that can safely be
+    /// sent twice. Post inits and token exchanges may have been processed by TikTok despite any
+    /// of these errors, so they are only retried on `ConnectFailed`.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            TikTokApiError::RateLimitExceeded(_)
+            | TikTokApiError::InternalError(_)
+            | TikTokApiError::ConnectFailed(_)
+            | TikTokApiError::RequestFailed(_) => true,
+            TikTokApiError::ApiError(details)
+            | TikTokApiError::Unknown(details)
+            | TikTokApiError::EmptyResponse(details)
+            | TikTokApiError::NonJsonResponse(details) => is_transient_status(details.status),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the access token is invalid, expired, or lacks a scope, so the user has
+    /// to refresh the token or authorize again.
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self,
+            TikTokApiError::AccessTokenInvalid(_)
+                | TikTokApiError::ScopeNotAuthorized(_)
+                | TikTokApiError::ScopePermissionMissed(_)
+                | TikTokApiError::MissingScopes(_)
+                | TikTokApiError::RefreshTokenExpired(_)
+        )
+    }
+
+    /// Records the response status and the request an API error came from.
+    pub(crate) fn with_request(mut self, status: u16, method: &str, path: &str) -> Self {
+        if let Some(details) = self.details_mut() {
+            details.status = status;
+            details.method = method.to_string();
+            details.path = path.to_string();
+        }
+        self
+    }
+}
+
+fn is_transient_status(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
+fn describe_request(details: &ApiErrorDetails) -> String {
+    if details.method.is_empty() {
+        String::new()
+    } else {
+        format!(" for {} {}", details.method, details.path)
+    }
+}
+
+fn describe_scopes(scopes: &[TikTokScope]) -> String {
+    scopes
+        .iter()
+        .map(TikTokScope::as_str)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn describe_violations<T: ToString>(violations: &[T]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_response(code: &str) -> ErrorResponse {
+        ErrorResponse {
+            code: code.to_string(),
+            message: "The field max_count is invalid".to_string(),
+            log_id: "202409011200".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_error_keeps_message_and_request() {
+        let error = TikTokApiError::from(error_response("invalid_params")).with_request(
+            400,
+            "POST",
+            "/v2/research/video/query/",
+        );
+
+        assert!(matches!(error, TikTokApiError::InvalidParams(_)));
+        assert_eq!(error.log_id(), Some("202409011200"));
+        let details = error.details().unwrap();
+        assert_eq!(details.status, 400);
+        assert_eq!(details.message, "The field max_count is invalid");
+        assert!(error.to_string().ends_with(
+            "Message: The field max_count is invalid. POST /v2/research/video/query/ returned HTTP 400. Log ID: 202409011200"
+        ));
+        assert!(!error.is_retryable());
+        assert!(!error.is_auth_error());
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert!(TikTokApiError::from(error_response("rate_limit_exceeded")).is_retryable());
+        assert!(TikTokApiError::from(error_response("access_token_invalid")).is_auth_error());
+        assert!(TikTokApiError::from(error_response("scope_not_authorized")).is_auth_error());
+
+        let missing =
+            TikTokApiError::MissingScopes(vec![TikTokScope::VideoList, TikTokScope::UserInfoStats]);
+        assert!(missing.is_auth_error());
+        assert!(missing.to_string().ends_with("video.list,user.info.stats"));
+
+        let unknown = TikTokApiError::from(error_response("something_new"));
+        assert!(matches!(unknown, TikTokApiError::Unknown(_)));
+        assert!(!unknown.is_retryable());
+        assert!(unknown
+            .with_request(503, "GET", "/v2/user/info/")
+            .is_retryable());
+    }
+}
@@ -1,10 +1,15 @@
 #![allow(dead_code)]
 
+pub mod api;
 pub mod auth;
 pub mod client;
 pub mod creator;
 pub mod direct_post;
 pub mod error;
+pub mod media;
+pub mod pagination;
 pub mod research;
 pub mod user;
 pub mod videos;
+
+pub use api::{TikTokClient, TikTokClientBuilder};
@@ -0,0 +1,89 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::TikTokApiError;
+
+use super::{mp4, webm, VideoInfo};
+
+const EBML_MAGIC: [u8; 4] = [0x1A, 0x45, 0xDF, 0xA3];
+
+/// Reads the headers of an MP4, MOV or WebM file.
+///
+/// Only the container headers are read, never the media data, so this is fast even for large
+/// files. The work runs on a blocking thread.
+///
+/// # Arguments
+///
+/// * `path` - The path to the video file.
+///
+/// # Returns
+///
+/// * `Result<VideoInfo, TikTokApiError>` - The video information, or `InvalidMedia` if the
+///   file is not a video this crate can read.
+pub async fn inspect_video(path: impl AsRef<Path>) -> Result<VideoInfo, TikTokApiError> {
+    let path = path.as_ref().to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let file = File::open(&path).map_err(|e| TikTokApiError::Io(e.to_string()))?;
+        inspect_reader(&mut BufReader::new(file))
+    })
+    .await
+    .map_err(|e| TikTokApiError::Io(e.to_string()))?
+}
+
+/// Reads the headers of an MP4, MOV or WebM video from `reader`.
+///
+/// # Arguments
+///
+/// * `reader` - The video, which is read from its start.
+///
+/// # Returns
+///
+/// * `Result<VideoInfo, TikTokApiError>` - The video information, or `InvalidMedia` if the
+///   data is not a video this crate can read.
+pub fn inspect_reader<R: Read + Seek>(reader: &mut R) -> Result<VideoInfo, TikTokApiError> {
+    let size = reader.seek(SeekFrom::End(0)).map_err(io_error)?;
+    reader.seek(SeekFrom::Start(0)).map_err(io_error)?;
+
+    let mut magic = [0; 8];
+    if size < magic.len() as u64 {
+        return Err(invalid("the file is too small to be a video"));
+    }
+    reader.read_exact(&mut magic).map_err(io_error)?;
+
+    if magic[..4] == EBML_MAGIC {
+        webm::parse(reader, size)
+    } else if mp4::is_box_type(&magic[4..]) {
+        mp4::parse(reader, size)
+    } else {
+        Err(invalid("the container is not MP4, MOV or WebM"))
+    }
+}
+
+pub(super) fn invalid(message: &str) -> TikTokApiError {
+    TikTokApiError::InvalidMedia(message.to_string())
+}
+
+pub(super) fn io_error(error: io::Error) -> TikTokApiError {
+    if error.kind() == io::ErrorKind::UnexpectedEof {
+        invalid("the file ends inside a header")
+    } else {
+        TikTokApiError::Io(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_inspect_reader_rejects_unknown_data() {
+        let result = inspect_reader(&mut Cursor::new(b"GIF89a not a video".to_vec()));
+        assert!(matches!(result, Err(TikTokApiError::InvalidMedia(_))));
+
+        let result = inspect_reader(&mut Cursor::new(b"tiny".to_vec()));
+        assert!(matches!(result, Err(TikTokApiError::InvalidMedia(_))));
+    }
+}
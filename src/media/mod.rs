@@ -0,0 +1,8 @@
+mod model;
+pub use model::*;
+
+mod inspect;
+pub use inspect::*;
+
+mod mp4;
+mod webm;
@@ -0,0 +1,248 @@
+use std::fmt;
+use std::time::Duration;
+
+/// The largest video TikTok accepts, 4 GB.
+pub const MAX_VIDEO_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+
+/// The longest video TikTok accepts. Most creators are limited further, see
+/// `CreatorData::max_video_post_duration_sec`.
+pub const MAX_VIDEO_DURATION: Duration = Duration::from_secs(10 * 60);
+
+/// The lowest frame rate TikTok accepts.
+pub const MIN_FRAME_RATE: f64 = 23.0;
+
+/// The highest frame rate TikTok accepts.
+pub const MAX_FRAME_RATE: f64 = 60.0;
+
+/// The smallest width and height TikTok accepts, in pixels.
+pub const MIN_DIMENSION: u32 = 360;
+
+/// The largest width and height TikTok accepts, in pixels.
+pub const MAX_DIMENSION: u32 = 4096;
+
+/// The container format of a video file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Container {
+    Mp4,
+    Mov,
+    WebM,
+    /// A Matroska file that is not WebM, which TikTok does not accept.
+    Matroska,
+}
+
+impl Container {
+    /// Returns true if TikTok accepts videos in this container.
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, Container::Matroska)
+    }
+}
+
+impl fmt::Display for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Container::Mp4 => "MP4",
+            Container::Mov => "MOV",
+            Container::WebM => "WebM",
+            Container::Matroska => "Matroska",
+        })
+    }
+}
+
+/// The codec of the video track.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VideoCodec {
+    H264,
+    H265,
+    Vp8,
+    Vp9,
+    Av1,
+    /// Another codec, named by its MP4 sample entry type or Matroska codec ID.
+    Other(String),
+}
+
+impl VideoCodec {
+    /// Returns true if TikTok accepts videos encoded with this codec.
+    pub fn is_supported(&self) -> bool {
+        matches!(
+            self,
+            VideoCodec::H264 | VideoCodec::H265 | VideoCodec::Vp8 | VideoCodec::Vp9
+        )
+    }
+}
+
+impl fmt::Display for VideoCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VideoCodec::H264 => f.write_str("H.264"),
+            VideoCodec::H265 => f.write_str("H.265"),
+            VideoCodec::Vp8 => f.write_str("VP8"),
+            VideoCodec::Vp9 => f.write_str("VP9"),
+            VideoCodec::Av1 => f.write_str("AV1"),
+            VideoCodec::Other(codec) => f.write_str(codec),
+        }
+    }
+}
+
+/// What the headers of a video file tell about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VideoInfo {
+    pub container: Container,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The duration, if the headers declare it.
+    pub duration: Option<Duration>,
+    pub width: u32,
+    pub height: u32,
+    /// The average frame rate, if the headers allow computing it.
+    pub frame_rate: Option<f64>,
+    pub codec: VideoCodec,
+}
+
+impl VideoInfo {
+    /// Checks the video against TikTok's media requirements.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<MediaViolation>` - Every requirement the video breaks, empty if it is acceptable.
+    pub fn violations(&self) -> Vec<MediaViolation> {
+        let mut violations = Vec::new();
+
+        if !self.container.is_supported() {
+            violations.push(MediaViolation::UnsupportedContainer(self.container));
+        }
+        if !self.codec.is_supported() {
+            violations.push(MediaViolation::UnsupportedCodec(self.codec.clone()));
+        }
+        if self.size > MAX_VIDEO_SIZE {
+            violations.push(MediaViolation::TooLarge { size: self.size });
+        }
+        if let Some(duration) = self.duration {
+            if duration > MAX_VIDEO_DURATION {
+                violations.push(MediaViolation::TooLong { duration });
+            }
+        }
+        if let Some(frame_rate) = self.frame_rate {
+            // Frame rates such as 23.976 are stored as rationals, so allow for rounding.
+            if !(MIN_FRAME_RATE - 0.1..=MAX_FRAME_RATE + 0.1).contains(&frame_rate) {
+                violations.push(MediaViolation::FrameRateOutOfRange { frame_rate });
+            }
+        }
+        let dimensions = MIN_DIMENSION..=MAX_DIMENSION;
+        if !dimensions.contains(&self.width) || !dimensions.contains(&self.height) {
+            violations.push(MediaViolation::DimensionsOutOfRange {
+                width: self.width,
+                height: self.height,
+            });
+        }
+
+        violations
+    }
+}
+
+/// A TikTok media requirement that a video breaks.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MediaViolation {
+    UnsupportedContainer(Container),
+    UnsupportedCodec(VideoCodec),
+    TooLarge { size: u64 },
+    TooLong { duration: Duration },
+    FrameRateOutOfRange { frame_rate: f64 },
+    DimensionsOutOfRange { width: u32, height: u32 },
+}
+
+impl fmt::Display for MediaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaViolation::UnsupportedContainer(container) => {
+                write!(
+                    f,
+                    "{} files are not supported, use MP4, MOV or WebM",
+                    container
+                )
+            }
+            MediaViolation::UnsupportedCodec(codec) => {
+                write!(
+                    f,
+                    "{} is not supported, use H.264, H.265, VP8 or VP9",
+                    codec
+                )
+            }
+            MediaViolation::TooLarge { size } => write!(
+                f,
+                "the file has {} bytes, more than the {} byte limit",
+                size, MAX_VIDEO_SIZE
+            ),
+            MediaViolation::TooLong { duration } => write!(
+                f,
+                "the video lasts {:?}, longer than {:?}",
+                duration, MAX_VIDEO_DURATION
+            ),
+            MediaViolation::FrameRateOutOfRange { frame_rate } => write!(
+                f,
+                "the frame rate of {:.2} FPS is outside {}-{} FPS",
+                frame_rate, MIN_FRAME_RATE, MAX_FRAME_RATE
+            ),
+            MediaViolation::DimensionsOutOfRange { width, height } => write!(
+                f,
+                "{}x{} pixels is outside {}-{} pixels per side",
+                width, height, MIN_DIMENSION, MAX_DIMENSION
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info() -> VideoInfo {
+        VideoInfo {
+            container: Container::Mp4,
+            size: 1024,
+            duration: Some(Duration::from_secs(30)),
+            width: 1080,
+            height: 1920,
+            frame_rate: Some(29.97),
+            codec: VideoCodec::H264,
+        }
+    }
+
+    #[test]
+    fn test_violations_of_acceptable_video() {
+        assert!(info().violations().is_empty());
+        assert!(VideoInfo {
+            frame_rate: Some(23.976),
+            ..info()
+        }
+        .violations()
+        .is_empty());
+    }
+
+    #[test]
+    fn test_violations_lists_every_broken_requirement() {
+        let video = VideoInfo {
+            container: Container::Matroska,
+            duration: Some(Duration::from_secs(11 * 60)),
+            width: 320,
+            frame_rate: Some(120.0),
+            codec: VideoCodec::Av1,
+            ..info()
+        };
+
+        assert_eq!(
+            video.violations(),
+            vec![
+                MediaViolation::UnsupportedContainer(Container::Matroska),
+                MediaViolation::UnsupportedCodec(VideoCodec::Av1),
+                MediaViolation::TooLong {
+                    duration: Duration::from_secs(11 * 60)
+                },
+                MediaViolation::FrameRateOutOfRange { frame_rate: 120.0 },
+                MediaViolation::DimensionsOutOfRange {
+                    width: 320,
+                    height: 1920
+                },
+            ]
+        );
+    }
+}
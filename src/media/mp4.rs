@@ -0,0 +1,397 @@
+//! Parses the ISO base media file format used by MP4 and MOV files.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use crate::error::TikTokApiError;
+
+use super::inspect::{invalid, io_error};
+use super::{Container, VideoCodec, VideoInfo};
+
+/// The largest `moov` box read into memory. Real ones are a few megabytes at most.
+const MAX_MOOV_SIZE: u64 = 64 * 1024 * 1024;
+
+const TOP_LEVEL_TYPES: [&[u8; 4]; 6] = [b"ftyp", b"moov", b"mdat", b"free", b"skip", b"wide"];
+
+type BoxType = [u8; 4];
+
+/// Returns true if `kind` is a box type found at the start of MP4 and MOV files.
+pub(super) fn is_box_type(kind: &[u8]) -> bool {
+    TOP_LEVEL_TYPES.iter().any(|top| top.as_slice() == kind)
+}
+
+pub(super) fn parse<R: Read + Seek>(
+    reader: &mut R,
+    size: u64,
+) -> Result<VideoInfo, TikTokApiError> {
+    let mut container = Container::Mp4;
+    let mut position = 0;
+
+    while position + 8 <= size {
+        reader.seek(SeekFrom::Start(position)).map_err(io_error)?;
+        let (kind, header_len, box_len) = read_box_header(reader, size - position)?;
+        match &kind {
+            b"ftyp" => {
+                let mut major_brand = [0; 4];
+                reader.read_exact(&mut major_brand).map_err(io_error)?;
+                if &major_brand == b"qt  " {
+                    container = Container::Mov;
+                }
+            }
+            b"moov" => {
+                let body_len = box_len - header_len;
+                if body_len > MAX_MOOV_SIZE {
+                    return Err(invalid("the moov box is too large"));
+                }
+                let mut moov = vec![0; body_len as usize];
+                reader.read_exact(&mut moov).map_err(io_error)?;
+                return parse_moov(&moov, container, size);
+            }
+            _ => {}
+        }
+        position += box_len;
+    }
+
+    Err(invalid("the file has no moov box"))
+}
+
+/// Reads a box header, returning the box type, the header length and the box length.
+fn read_box_header<R: Read>(
+    reader: &mut R,
+    remaining: u64,
+) -> Result<(BoxType, u64, u64), TikTokApiError> {
+    let mut header = [0; 8];
+    reader.read_exact(&mut header).map_err(io_error)?;
+    let kind = [header[4], header[5], header[6], header[7]];
+
+    let (header_len, box_len) =
+        match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+            0 => (8, remaining),
+            1 => {
+                let mut large_size = [0; 8];
+                reader.read_exact(&mut large_size).map_err(io_error)?;
+                (16, u64::from_be_bytes(large_size))
+            }
+            box_len => (8, u64::from(box_len)),
+        };
+    if box_len < header_len || box_len > remaining {
+        return Err(invalid("a box length is out of range"));
+    }
+    Ok((kind, header_len, box_len))
+}
+
+/// Splits the content of a container box into its child boxes.
+fn children(data: &[u8]) -> Result<Vec<(BoxType, &[u8])>, TikTokApiError> {
+    let mut boxes = Vec::new();
+    let mut position = 0;
+
+    while position + 8 <= data.len() {
+        let (kind, header_len, box_len) =
+            read_box_header(&mut &data[position..], (data.len() - position) as u64)?;
+        let start = position + header_len as usize;
+        let end = position + box_len as usize;
+        boxes.push((kind, &data[start..end]));
+        position = end;
+    }
+    Ok(boxes)
+}
+
+fn child<'a>(data: &'a [u8], kind: &BoxType) -> Result<&'a [u8], TikTokApiError> {
+    children(data)?
+        .into_iter()
+        .find(|(child_kind, _)| child_kind == kind)
+        .map(|(_, body)| body)
+        .ok_or_else(|| {
+            TikTokApiError::InvalidMedia(format!(
+                "the file has no {} box",
+                String::from_utf8_lossy(kind)
+            ))
+        })
+}
+
+fn bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], TikTokApiError> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid("a box is shorter than its fields"))
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, TikTokApiError> {
+    Ok(u16::from_be_bytes(bytes(data, offset)?))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, TikTokApiError> {
+    Ok(u32::from_be_bytes(bytes(data, offset)?))
+}
+
+fn u64_at(data: &[u8], offset: usize) -> Result<u64, TikTokApiError> {
+    Ok(u64::from_be_bytes(bytes(data, offset)?))
+}
+
+/// Reads the timescale and duration of a `mvhd` or `mdhd` box.
+fn timing(data: &[u8]) -> Result<(u32, u64), TikTokApiError> {
+    match data.first() {
+        Some(1) => Ok((u32_at(data, 20)?, u64_at(data, 24)?)),
+        _ => Ok((u32_at(data, 12)?, u64::from(u32_at(data, 16)?))),
+    }
+}
+
+/// Converts a duration in `timescale` units, where zero and all ones mean the duration is
+/// unknown.
+fn seconds(duration: u64, timescale: u32) -> Result<Option<Duration>, TikTokApiError> {
+    if timescale == 0 || duration == 0 || duration == u64::from(u32::MAX) || duration == u64::MAX {
+        return Ok(None);
+    }
+    Duration::try_from_secs_f64(duration as f64 / f64::from(timescale))
+        .map(Some)
+        .map_err(|_| invalid("the duration is out of range"))
+}
+
+struct VideoTrack {
+    duration: Option<Duration>,
+    width: u32,
+    height: u32,
+    frame_rate: Option<f64>,
+    codec: VideoCodec,
+}
+
+fn parse_moov(moov: &[u8], container: Container, size: u64) -> Result<VideoInfo, TikTokApiError> {
+    let (timescale, duration) = timing(child(moov, b"mvhd")?)?;
+
+    for (kind, trak) in children(moov)? {
+        if &kind != b"trak" {
+            continue;
+        }
+        if let Some(track) = parse_video_track(trak)? {
+            return Ok(VideoInfo {
+                container,
+                size,
+                duration: match track.duration {
+                    Some(duration) => Some(duration),
+                    None => seconds(duration, timescale)?,
+                },
+                width: track.width,
+                height: track.height,
+                frame_rate: track.frame_rate,
+                codec: track.codec,
+            });
+        }
+    }
+
+    Err(invalid("the file has no video track"))
+}
+
+fn parse_video_track(trak: &[u8]) -> Result<Option<VideoTrack>, TikTokApiError> {
+    let mdia = child(trak, b"mdia")?;
+    let handler: BoxType = bytes(child(mdia, b"hdlr")?, 8)?;
+    if &handler != b"vide" {
+        return Ok(None);
+    }
+
+    let (timescale, duration) = timing(child(mdia, b"mdhd")?)?;
+    let stbl = child(child(mdia, b"minf")?, b"stbl")?;
+
+    // The first sample description follows the version, flags and entry count.
+    let stsd = child(stbl, b"stsd")?;
+    let sample_entry: BoxType = bytes(stsd, 12)?;
+    let width = u16_at(stsd, 40)?;
+    let height = u16_at(stsd, 42)?;
+
+    let stts = child(stbl, b"stts")?;
+    let mut samples = 0u64;
+    let mut ticks = 0u64;
+    for entry in 0..u32_at(stts, 4)? as usize {
+        let sample_count = u64::from(u32_at(stts, 8 + entry * 8)?);
+        let sample_delta = u64::from(u32_at(stts, 12 + entry * 8)?);
+        samples = samples
+            .checked_add(sample_count)
+            .ok_or_else(|| invalid("the sample table overflows"))?;
+        ticks = sample_count
+            .checked_mul(sample_delta)
+            .and_then(|entry_ticks| ticks.checked_add(entry_ticks))
+            .ok_or_else(|| invalid("the sample table overflows"))?;
+    }
+    let frame_rate =
+        (ticks > 0 && timescale > 0).then(|| samples as f64 * f64::from(timescale) / ticks as f64);
+
+    Ok(Some(VideoTrack {
+        duration: seconds(duration, timescale)?,
+        width: width.into(),
+        height: height.into(),
+        frame_rate,
+        codec: codec(&sample_entry),
+    }))
+}
+
+fn codec(sample_entry: &BoxType) -> VideoCodec {
+    match sample_entry {
+        b"avc1" | b"avc3" => VideoCodec::H264,
+        b"hvc1" | b"hev1" => VideoCodec::H265,
+        b"vp08" => VideoCodec::Vp8,
+        b"vp09" => VideoCodec::Vp9,
+        b"av01" => VideoCodec::Av1,
+        other => VideoCodec::Other(String::from_utf8_lossy(other).trim().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::media::inspect_reader;
+
+    fn mp4_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    fn full_box(kind: &[u8; 4], fields: &[u8]) -> Vec<u8> {
+        let mut payload = vec![0; 4];
+        payload.extend_from_slice(fields);
+        mp4_box(kind, &payload)
+    }
+
+    /// A version 0 `mvhd` or `mdhd` box body.
+    fn timing_fields(timescale: u32, duration: u32) -> Vec<u8> {
+        let mut fields = vec![0; 8];
+        fields.extend_from_slice(&timescale.to_be_bytes());
+        fields.extend_from_slice(&duration.to_be_bytes());
+        fields
+    }
+
+    /// A version 1 `mvhd` or `mdhd` box with a 64-bit duration.
+    fn timing_box_v1(kind: &[u8; 4], timescale: u32, duration: u64) -> Vec<u8> {
+        let mut payload = vec![1, 0, 0, 0];
+        payload.extend_from_slice(&[0; 16]);
+        payload.extend_from_slice(&timescale.to_be_bytes());
+        payload.extend_from_slice(&duration.to_be_bytes());
+        mp4_box(kind, &payload)
+    }
+
+    fn video_trak(codec: &[u8; 4], width: u16, height: u16) -> Vec<u8> {
+        // 300 samples of 1001 ticks at 30000 ticks per second: 29.97 FPS.
+        video_trak_with(
+            codec,
+            width,
+            height,
+            full_box(b"mdhd", &timing_fields(30000, 300 * 1001)),
+            &[(300, 1001)],
+        )
+    }
+
+    fn video_trak_with(
+        codec: &[u8; 4],
+        width: u16,
+        height: u16,
+        mdhd: Vec<u8>,
+        stts_entries: &[(u32, u32)],
+    ) -> Vec<u8> {
+        let mut sample_entry = vec![0; 24];
+        sample_entry.extend_from_slice(&width.to_be_bytes());
+        sample_entry.extend_from_slice(&height.to_be_bytes());
+        let mut stsd = 1u32.to_be_bytes().to_vec();
+        stsd.extend(mp4_box(codec, &sample_entry));
+
+        let mut stts = (stts_entries.len() as u32).to_be_bytes().to_vec();
+        for (sample_count, sample_delta) in stts_entries {
+            stts.extend_from_slice(&sample_count.to_be_bytes());
+            stts.extend_from_slice(&sample_delta.to_be_bytes());
+        }
+
+        let stbl = [full_box(b"stsd", &stsd), full_box(b"stts", &stts)].concat();
+        let mut hdlr = vec![0; 4];
+        hdlr.extend_from_slice(b"vide");
+        let mdia = [
+            mdhd,
+            full_box(b"hdlr", &hdlr),
+            mp4_box(b"minf", &mp4_box(b"stbl", &stbl)),
+        ]
+        .concat();
+        mp4_box(b"trak", &mp4_box(b"mdia", &mdia))
+    }
+
+    fn sound_trak() -> Vec<u8> {
+        let mut hdlr = vec![0; 4];
+        hdlr.extend_from_slice(b"soun");
+        mp4_box(b"trak", &mp4_box(b"mdia", &full_box(b"hdlr", &hdlr)))
+    }
+
+    fn mp4_file(brand: &[u8; 4], codec: &[u8; 4]) -> Vec<u8> {
+        mp4_file_with(
+            brand,
+            full_box(b"mvhd", &timing_fields(1000, 10_010)),
+            video_trak(codec, 1080, 1920),
+        )
+    }
+
+    fn mp4_file_with(brand: &[u8; 4], mvhd: Vec<u8>, video_trak: Vec<u8>) -> Vec<u8> {
+        let mut ftyp = brand.to_vec();
+        ftyp.extend_from_slice(&[0; 4]);
+        let moov = [mvhd, sound_trak(), video_trak].concat();
+        // The media data comes first, as in files that were not optimized for streaming.
+        [
+            mp4_box(b"ftyp", &ftyp),
+            mp4_box(b"mdat", &[0; 64]),
+            mp4_box(b"moov", &moov),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_parse_mp4() {
+        let file = mp4_file(b"isom", b"avc1");
+        let info = inspect_reader(&mut Cursor::new(file.clone())).unwrap();
+
+        assert_eq!(info.container, Container::Mp4);
+        assert_eq!(info.size, file.len() as u64);
+        assert_eq!(info.codec, VideoCodec::H264);
+        assert_eq!((info.width, info.height), (1080, 1920));
+        assert_eq!(info.duration, Some(Duration::from_secs_f64(10.01)));
+        assert!((info.frame_rate.unwrap() - 29.97).abs() < 0.01);
+        assert!(info.violations().is_empty());
+    }
+
+    #[test]
+    fn test_parse_mov_with_other_codec() {
+        let info = inspect_reader(&mut Cursor::new(mp4_file(b"qt  ", b"ap4h"))).unwrap();
+
+        assert_eq!(info.container, Container::Mov);
+        assert_eq!(info.codec, VideoCodec::Other("ap4h".to_string()));
+    }
+
+    #[test]
+    fn test_parse_truncated_mp4() {
+        let mut file = mp4_file(b"isom", b"hvc1");
+        file.truncate(file.len() - 10);
+
+        let result = inspect_reader(&mut Cursor::new(file));
+        assert!(matches!(result, Err(TikTokApiError::InvalidMedia(_))));
+    }
+
+    #[test]
+    fn test_parse_mp4_with_malformed_tables() {
+        let inspect = |mvhd: Vec<u8>, mdhd: Vec<u8>, stts_entries: &[(u32, u32)]| {
+            let trak = video_trak_with(b"avc1", 1080, 1920, mdhd, stts_entries);
+            inspect_reader(&mut Cursor::new(mp4_file_with(b"isom", mvhd, trak)))
+        };
+        let frames = [(300, 1001)];
+
+        // A 64-bit duration beyond `Duration::MAX`.
+        let huge = timing_box_v1(b"mdhd", 1, u64::MAX - 1);
+        let result = inspect(timing_box_v1(b"mvhd", 1, 10), huge, &frames);
+        assert!(matches!(result, Err(TikTokApiError::InvalidMedia(_))));
+
+        // All ones means unknown, in the track and in the movie header.
+        let unknown = timing_box_v1(b"mdhd", 1, u64::MAX);
+        let info = inspect(timing_box_v1(b"mvhd", 1, u64::MAX), unknown, &frames).unwrap();
+        assert_eq!(info.duration, None);
+
+        // Sample counts and deltas whose totals do not fit in 64 bits.
+        let mdhd = full_box(b"mdhd", &timing_fields(30000, 300 * 1001));
+        let mvhd = full_box(b"mvhd", &timing_fields(1000, 10_010));
+        let result = inspect(mvhd, mdhd, &[(u32::MAX, u32::MAX); 3]);
+        assert!(matches!(result, Err(TikTokApiError::InvalidMedia(_))));
+    }
+}
@@ -0,0 +1,323 @@
+//! Parses the EBML headers of WebM and Matroska files.
+
+use std::io::{Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use crate::error::TikTokApiError;
+
+use super::inspect::{invalid, io_error};
+use super::{Container, VideoCodec, VideoInfo};
+
+const EBML: u32 = 0x1A45_DFA3;
+const DOC_TYPE: u32 = 0x4282;
+const SEGMENT: u32 = 0x1853_8067;
+const INFO: u32 = 0x1549_A966;
+const TIMECODE_SCALE: u32 = 0x2A_D7B1;
+const DURATION: u32 = 0x4489;
+const TRACKS: u32 = 0x1654_AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_TYPE: u32 = 0x83;
+const CODEC_ID: u32 = 0x86;
+const DEFAULT_DURATION: u32 = 0x23_E383;
+const VIDEO: u32 = 0xE0;
+const PIXEL_WIDTH: u32 = 0xB0;
+const PIXEL_HEIGHT: u32 = 0xBA;
+const CLUSTER: u32 = 0x1F43_B675;
+
+const VIDEO_TRACK_TYPE: u64 = 1;
+const DEFAULT_TIMECODE_SCALE: u64 = 1_000_000;
+
+/// The largest header element read into memory.
+const MAX_ELEMENT_SIZE: u64 = 16 * 1024 * 1024;
+
+/// The size of an element, `None` when the writer left it unknown.
+type ElementSize = Option<u64>;
+
+pub(super) fn parse<R: Read + Seek>(
+    reader: &mut R,
+    size: u64,
+) -> Result<VideoInfo, TikTokApiError> {
+    reader.seek(SeekFrom::Start(0)).map_err(io_error)?;
+    let (id, header_size) = read_element_header(reader)?;
+    if id != EBML {
+        return Err(invalid("the file has no EBML header"));
+    }
+    let header = read_body(reader, header_size)?;
+    let container = match find(&header, DOC_TYPE)? {
+        Some(b"webm") => Container::WebM,
+        _ => Container::Matroska,
+    };
+
+    let (id, segment_size) = read_element_header(reader)?;
+    if id != SEGMENT {
+        return Err(invalid("the file has no segment"));
+    }
+    let segment_start = reader.stream_position().map_err(io_error)?;
+    let segment_end = segment_size.map_or(size, |len| (segment_start + len).min(size));
+
+    let mut info = None;
+    let mut tracks = None;
+    let mut position = segment_start;
+    while position < segment_end && (info.is_none() || tracks.is_none()) {
+        reader.seek(SeekFrom::Start(position)).map_err(io_error)?;
+        let (id, element_size) = read_element_header(reader)?;
+        let body_start = reader.stream_position().map_err(io_error)?;
+        match id {
+            INFO => info = Some(read_body(reader, element_size)?),
+            TRACKS => tracks = Some(read_body(reader, element_size)?),
+            // Media data follows the headers.
+            CLUSTER => break,
+            _ => {}
+        }
+        match element_size {
+            Some(len) => position = body_start + len,
+            None => break,
+        }
+    }
+
+    let tracks = tracks.ok_or_else(|| invalid("the file has no tracks"))?;
+    let duration = match info {
+        Some(info) => parse_duration(&info)?,
+        None => None,
+    };
+    for (id, entry) in elements(&tracks)? {
+        if id != TRACK_ENTRY || find_uint(entry, TRACK_TYPE)? != Some(VIDEO_TRACK_TYPE) {
+            continue;
+        }
+        let video = find(entry, VIDEO)?.ok_or_else(|| invalid("the video track has no size"))?;
+        let codec_id = find(entry, CODEC_ID)?.unwrap_or_default();
+        let frame_rate = find_uint(entry, DEFAULT_DURATION)?
+            .filter(|&nanos| nanos > 0)
+            .map(|nanos| 1_000_000_000.0 / nanos as f64);
+
+        return Ok(VideoInfo {
+            container,
+            size,
+            duration,
+            width: find_uint(video, PIXEL_WIDTH)?.unwrap_or_default() as u32,
+            height: find_uint(video, PIXEL_HEIGHT)?.unwrap_or_default() as u32,
+            frame_rate,
+            codec: codec(&String::from_utf8_lossy(codec_id)),
+        });
+    }
+
+    Err(invalid("the file has no video track"))
+}
+
+fn parse_duration(info: &[u8]) -> Result<Option<Duration>, TikTokApiError> {
+    let timecode_scale = find_uint(info, TIMECODE_SCALE)?.unwrap_or(DEFAULT_TIMECODE_SCALE);
+    let duration = match find(info, DURATION)? {
+        Some(bytes) => float(bytes)?,
+        None => return Ok(None),
+    };
+    let nanos = duration * timecode_scale as f64;
+    if !nanos.is_finite() || nanos <= 0.0 {
+        return Ok(None);
+    }
+    Duration::try_from_secs_f64(nanos / 1e9)
+        .map(Some)
+        .map_err(|_| invalid("the duration is out of range"))
+}
+
+fn codec(codec_id: &str) -> VideoCodec {
+    match codec_id {
+        "V_MPEG4/ISO/AVC" => VideoCodec::H264,
+        "V_MPEGH/ISO/HEVC" => VideoCodec::H265,
+        "V_VP8" => VideoCodec::Vp8,
+        "V_VP9" => VideoCodec::Vp9,
+        "V_AV1" => VideoCodec::Av1,
+        other => VideoCodec::Other(other.to_string()),
+    }
+}
+
+/// Reads a variable length integer, returning its value with the length marker removed when
+/// `strip_marker` is set, and its length in bytes.
+fn read_vint<R: Read>(reader: &mut R, strip_marker: bool) -> Result<(u64, usize), TikTokApiError> {
+    let mut first = [0; 1];
+    reader.read_exact(&mut first).map_err(io_error)?;
+    let len = first[0].leading_zeros() as usize + 1;
+    if len > 8 {
+        return Err(invalid("an EBML integer has no length marker"));
+    }
+
+    let mut value = if strip_marker {
+        u64::from(first[0] & 0xFFu8.checked_shr(len as u32).unwrap_or(0))
+    } else {
+        u64::from(first[0])
+    };
+    let mut rest = [0; 7];
+    reader.read_exact(&mut rest[..len - 1]).map_err(io_error)?;
+    for byte in &rest[..len - 1] {
+        value = (value << 8) | u64::from(*byte);
+    }
+    Ok((value, len))
+}
+
+fn read_element_header<R: Read>(reader: &mut R) -> Result<(u32, ElementSize), TikTokApiError> {
+    let (id, id_len) = read_vint(reader, false)?;
+    if id_len > 4 {
+        return Err(invalid("an EBML element ID is too long"));
+    }
+    let (size, size_len) = read_vint(reader, true)?;
+    let unknown = size == (1 << (7 * size_len)) - 1;
+    Ok((id as u32, (!unknown).then_some(size)))
+}
+
+fn read_body<R: Read>(reader: &mut R, size: ElementSize) -> Result<Vec<u8>, TikTokApiError> {
+    let size = size.ok_or_else(|| invalid("a header element has an unknown size"))?;
+    if size > MAX_ELEMENT_SIZE {
+        return Err(invalid("a header element is too large"));
+    }
+    let mut body = vec![0; size as usize];
+    reader.read_exact(&mut body).map_err(io_error)?;
+    Ok(body)
+}
+
+/// Splits the content of a master element into its child elements.
+fn elements(mut data: &[u8]) -> Result<Vec<(u32, &[u8])>, TikTokApiError> {
+    let mut children = Vec::new();
+    while !data.is_empty() {
+        let (id, size) = read_element_header(&mut data)?;
+        let size = size.map_or(data.len(), |size| size as usize);
+        if size > data.len() {
+            return Err(invalid("an EBML element is longer than its parent"));
+        }
+        children.push((id, &data[..size]));
+        data = &data[size..];
+    }
+    Ok(children)
+}
+
+fn find(data: &[u8], id: u32) -> Result<Option<&[u8]>, TikTokApiError> {
+    Ok(elements(data)?
+        .into_iter()
+        .find(|(child_id, _)| *child_id == id)
+        .map(|(_, body)| body))
+}
+
+fn find_uint(data: &[u8], id: u32) -> Result<Option<u64>, TikTokApiError> {
+    match find(data, id)? {
+        Some(bytes) if bytes.len() <= 8 => Ok(Some(
+            bytes
+                .iter()
+                .fold(0, |value, byte| (value << 8) | u64::from(*byte)),
+        )),
+        Some(_) => Err(invalid("an EBML unsigned integer is too long")),
+        None => Ok(None),
+    }
+}
+
+fn float(bytes: &[u8]) -> Result<f64, TikTokApiError> {
+    match bytes.len() {
+        4 => Ok(f32::from_be_bytes(bytes.try_into().unwrap()).into()),
+        8 => Ok(f64::from_be_bytes(bytes.try_into().unwrap())),
+        _ => Err(invalid("an EBML float has an invalid length")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::media::inspect_reader;
+
+    fn element(id: u32, payload: &[u8]) -> Vec<u8> {
+        let id_bytes = id.to_be_bytes();
+        let skip = id_bytes.iter().take_while(|byte| **byte == 0).count();
+        let mut data = id_bytes[skip..].to_vec();
+        // An eight byte size, as some muxers write.
+        data.push(0x01);
+        data.extend_from_slice(&(payload.len() as u64).to_be_bytes()[1..]);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    fn uint(id: u32, value: u64) -> Vec<u8> {
+        element(id, &value.to_be_bytes())
+    }
+
+    fn webm_file(doc_type: &str, codec_id: &str) -> Vec<u8> {
+        webm_file_with(doc_type, codec_id, 12_500.0)
+    }
+
+    fn webm_file_with(doc_type: &str, codec_id: &str, duration: f64) -> Vec<u8> {
+        let info = [
+            uint(TIMECODE_SCALE, 1_000_000),
+            element(DURATION, &duration.to_be_bytes()),
+        ]
+        .concat();
+        let audio = element(
+            TRACK_ENTRY,
+            &[uint(TRACK_TYPE, 2), element(CODEC_ID, b"A_OPUS")].concat(),
+        );
+        let video = element(
+            TRACK_ENTRY,
+            &[
+                uint(TRACK_TYPE, VIDEO_TRACK_TYPE),
+                element(CODEC_ID, codec_id.as_bytes()),
+                uint(DEFAULT_DURATION, 33_333_333),
+                element(
+                    VIDEO,
+                    &[uint(PIXEL_WIDTH, 720), uint(PIXEL_HEIGHT, 1280)].concat(),
+                ),
+            ]
+            .concat(),
+        );
+        let tracks = element(TRACKS, &[audio, video].concat());
+
+        // A live recording leaves the segment size unknown.
+        let mut segment = vec![
+            0x18, 0x53, 0x80, 0x67, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        ];
+        segment.extend(element(INFO, &info));
+        segment.extend(tracks);
+        segment.extend(element(CLUSTER, &[0; 32]));
+
+        [
+            element(EBML, &element(DOC_TYPE, doc_type.as_bytes())),
+            segment,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_parse_webm() {
+        let file = webm_file("webm", "V_VP9");
+        let info = inspect_reader(&mut Cursor::new(file.clone())).unwrap();
+
+        assert_eq!(info.container, Container::WebM);
+        assert_eq!(info.size, file.len() as u64);
+        assert_eq!(info.codec, VideoCodec::Vp9);
+        assert_eq!((info.width, info.height), (720, 1280));
+        assert_eq!(info.duration, Some(Duration::from_millis(12_500)));
+        assert!((info.frame_rate.unwrap() - 30.0).abs() < 0.01);
+        assert!(info.violations().is_empty());
+    }
+
+    #[test]
+    fn test_parse_matroska() {
+        let info =
+            inspect_reader(&mut Cursor::new(webm_file("matroska", "V_MPEG4/ISO/AVC"))).unwrap();
+
+        assert_eq!(info.container, Container::Matroska);
+        assert_eq!(info.codec, VideoCodec::H264);
+        assert_eq!(
+            info.violations(),
+            vec![crate::media::MediaViolation::UnsupportedContainer(
+                Container::Matroska
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_webm_with_malformed_duration() {
+        // A finite duration beyond `Duration::MAX`.
+        let result = inspect_reader(&mut Cursor::new(webm_file_with("webm", "V_VP9", 1e300)));
+        assert!(matches!(result, Err(TikTokApiError::InvalidMedia(_))));
+
+        let info =
+            inspect_reader(&mut Cursor::new(webm_file_with("webm", "V_VP9", f64::NAN))).unwrap();
+        assert_eq!(info.duration, None);
+    }
+}
@@ -0,0 +1,209 @@
+use std::future::Future;
+
+use futures::{stream, Stream, StreamExt};
+
+use crate::error::TikTokApiError;
+
+/// A page of a cursor-paginated TikTok endpoint.
+pub trait CursorPage {
+    type Item;
+
+    /// The cursor to request the next page with.
+    fn cursor(&self) -> i64;
+
+    /// Whether there are more pages after this one.
+    fn has_more(&self) -> bool;
+
+    /// The search ID TikTok requires to be sent back with the next page, if any.
+    fn search_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// Consumes the page and returns its items.
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+/// Position of a page request.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PageCursor {
+    pub cursor: Option<i64>,
+    pub search_id: Option<String>,
+}
+
+impl PageCursor {
+    /// Creates a position starting at the given cursor.
+    pub fn new(cursor: Option<i64>) -> Self {
+        Self {
+            cursor,
+            search_id: None,
+        }
+    }
+
+    /// Sets the search ID to send with the request.
+    pub fn with_search_id(mut self, search_id: Option<String>) -> Self {
+        self.search_id = search_id;
+        self
+    }
+}
+
+/// Turns a page fetching function into a stream of items.
+///
+/// `fetch` is called with the position of each page, starting at `start`, until a page reports
+/// that there are no more results. The stream ends after yielding the first error.
+///
+/// # Arguments
+///
+/// * `start` - The position of the first page.
+/// * `fetch` - Fetches the page at the given position.
+pub fn paginate<P, F, Fut>(
+    start: PageCursor,
+    fetch: F,
+) -> impl Stream<Item = Result<P::Item, TikTokApiError>>
+where
+    P: CursorPage,
+    F: FnMut(PageCursor) -> Fut,
+    Fut: Future<Output = Result<P, TikTokApiError>>,
+{
+    let pages = stream::unfold((Some(start), fetch), |(position, mut fetch)| async move {
+        let position = position?;
+        match fetch(position.clone()).await {
+            Ok(page) => {
+                let next = next_position(&position, &page);
+                Some((Ok(page.into_items()), (next, fetch)))
+            }
+            Err(e) => Some((Err(e), (None, fetch))),
+        }
+    });
+
+    pages.flat_map(|page| {
+        stream::iter(match page {
+            Ok(items) => items.into_iter().map(Ok).collect::<Vec<_>>(),
+            Err(e) => vec![Err(e)],
+        })
+    })
+}
+
+/// Returns the position of the page after `page`, or `None` when the listing is complete.
+///
+/// Also stops when a page does not move the cursor forward, so a misbehaving endpoint cannot
+/// make the stream loop forever.
+fn next_position<P: CursorPage>(current: &PageCursor, page: &P) -> Option<PageCursor> {
+    if !page.has_more() {
+        return None;
+    }
+    let stalled = current.cursor == Some(page.cursor());
+    if stalled {
+        return None;
+    }
+    Some(PageCursor {
+        cursor: Some(page.cursor()),
+        search_id: page
+            .search_id()
+            .map(str::to_string)
+            .or_else(|| current.search_id.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    struct Page {
+        items: Vec<u32>,
+        cursor: i64,
+        has_more: bool,
+        search_id: Option<String>,
+    }
+
+    impl CursorPage for Page {
+        type Item = u32;
+
+        fn cursor(&self) -> i64 {
+            self.cursor
+        }
+
+        fn has_more(&self) -> bool {
+            self.has_more
+        }
+
+        fn search_id(&self) -> Option<&str> {
+            self.search_id.as_deref()
+        }
+
+        fn into_items(self) -> Vec<u32> {
+            self.items
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paginate_carries_cursor_and_search_id() {
+        let mut requests = Vec::new();
+        let items: Vec<u32> = paginate(PageCursor::default(), |position| {
+            requests.push(position.clone());
+            async move {
+                Ok(match position.cursor {
+                    None => Page {
+                        items: vec![1, 2],
+                        cursor: 2,
+                        has_more: true,
+                        search_id: Some("search".into()),
+                    },
+                    Some(2) => Page {
+                        items: vec![],
+                        cursor: 4,
+                        has_more: true,
+                        search_id: None,
+                    },
+                    _ => Page {
+                        items: vec![3],
+                        cursor: 5,
+                        has_more: false,
+                        search_id: None,
+                    },
+                })
+            }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(
+            requests,
+            [
+                PageCursor::default(),
+                PageCursor::new(Some(2)).with_search_id(Some("search".into())),
+                PageCursor::new(Some(4)).with_search_id(Some("search".into())),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paginate_stops_after_error() {
+        let results: Vec<Result<u32, TikTokApiError>> =
+            paginate(PageCursor::default(), |_| async {
+                Err::<Page, _>(TikTokApiError::InternalError(Box::default()))
+            })
+            .collect()
+            .await;
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_stops_when_cursor_does_not_move() {
+        let items: Vec<u32> = paginate(PageCursor::new(Some(7)), |_| async {
+            Ok(Page {
+                items: vec![],
+                cursor: 7,
+                has_more: true,
+                search_id: None,
+            })
+        })
+        .try_collect()
+        .await
+        .unwrap();
+        assert!(items.is_empty());
+    }
+}
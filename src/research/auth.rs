@@ -0,0 +1,87 @@
+use crate::auth::{AccessToken, AccessTokenResponse, OwnedAccessToken, StoredToken, TikTokScope};
+use crate::error::TikTokApiError;
+
+/// How a research request is authenticated.
+///
+/// Plain strings and `AccessToken`s convert into `ResearchAuth::Token`, so existing calls
+/// passing a client access token keep working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResearchAuth<'a> {
+    /// Authenticates with the given client access token.
+    Token(AccessToken<'a>),
+    /// Authenticates with a client access token from the `TokenCache` set with
+    /// `Service::with_token_cache`.
+    Cached,
+}
+
+impl ResearchAuth<'_> {
+    /// Fails with `MissingScopes` if the granted scopes of the token are known and lack any of
+    /// `required`. Cached tokens are not checked.
+    pub(crate) fn require(&self, required: &[TikTokScope]) -> Result<(), TikTokApiError> {
+        match self {
+            ResearchAuth::Token(token) => token.require(required),
+            ResearchAuth::Cached => Ok(()),
+        }
+    }
+}
+
+impl<'a> From<AccessToken<'a>> for ResearchAuth<'a> {
+    fn from(token: AccessToken<'a>) -> Self {
+        ResearchAuth::Token(token)
+    }
+}
+
+impl<'a> From<&'a str> for ResearchAuth<'a> {
+    fn from(token: &'a str) -> Self {
+        ResearchAuth::Token(token.into())
+    }
+}
+
+impl<'a> From<&&'a str> for ResearchAuth<'a> {
+    fn from(token: &&'a str) -> Self {
+        ResearchAuth::Token(token.into())
+    }
+}
+
+impl<'a> From<&'a String> for ResearchAuth<'a> {
+    fn from(token: &'a String) -> Self {
+        ResearchAuth::Token(token.into())
+    }
+}
+
+impl<'a> From<&'a StoredToken> for ResearchAuth<'a> {
+    fn from(token: &'a StoredToken) -> Self {
+        ResearchAuth::Token(token.into())
+    }
+}
+
+impl<'a> From<&'a AccessTokenResponse> for ResearchAuth<'a> {
+    fn from(token: &'a AccessTokenResponse) -> Self {
+        ResearchAuth::Token(token.into())
+    }
+}
+
+/// A `ResearchAuth` owned by a stream.
+#[derive(Debug, Clone)]
+pub(crate) enum OwnedResearchAuth {
+    Token(OwnedAccessToken),
+    Cached,
+}
+
+impl From<ResearchAuth<'_>> for OwnedResearchAuth {
+    fn from(auth: ResearchAuth<'_>) -> Self {
+        match auth {
+            ResearchAuth::Token(token) => OwnedResearchAuth::Token(token.into()),
+            ResearchAuth::Cached => OwnedResearchAuth::Cached,
+        }
+    }
+}
+
+impl OwnedResearchAuth {
+    pub(crate) fn as_auth(&self) -> ResearchAuth<'_> {
+        match self {
+            OwnedResearchAuth::Token(token) => ResearchAuth::Token(token.as_token()),
+            OwnedResearchAuth::Cached => ResearchAuth::Cached,
+        }
+    }
+}
@@ -1,5 +1,11 @@
+mod auth;
+pub use auth::*;
+
 mod model;
 pub use model::*;
 
 mod service;
 pub use service::*;
+
+mod query;
+pub use query::*;
@@ -1,9 +1,13 @@
+use std::fmt;
+
+use chrono::{Duration, NaiveDate};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
 use crate::error::ErrorResponse;
+use crate::pagination::CursorPage;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ResearchUserInfoRequest {
     pub username: String,
 }
@@ -35,7 +39,7 @@ pub struct ResearchUserInfoData {
     pub video_count: i32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ResearchLikedVideosRequest {
     pub username: String,
     pub max_count: Option<i64>,
@@ -55,7 +59,23 @@ pub struct ResearchUserLikedVideosData {
     pub has_more: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl CursorPage for ResearchUserLikedVideosData {
+    type Item = ResearchVideo;
+
+    fn cursor(&self) -> i64 {
+        self.cursor
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    fn into_items(self) -> Vec<ResearchVideo> {
+        self.user_liked_videos
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ResearchPinnedVideosRequest {
     pub username: String,
 }
@@ -71,7 +91,7 @@ pub struct ResearchPinnedVideosData {
     pub user_pinned_videos: Vec<ResearchVideo>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ResearchUserFollowersRequest {
     pub username: String,
     pub max_count: Option<i64>,
@@ -95,13 +115,29 @@ pub struct ResearchUserFollowerData {
     pub has_more: bool,
 }
 
+impl CursorPage for ResearchUserFollowerData {
+    type Item = ResearchUserInfo;
+
+    fn cursor(&self) -> i64 {
+        self.cursor
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    fn into_items(self) -> Vec<ResearchUserInfo> {
+        self.user_followers
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ResearchUserInfo {
     pub display_name: String,
     pub username: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ResearchUserFollowingRequest {
     pub username: String,
     pub max_count: Option<i64>,
@@ -121,7 +157,23 @@ pub struct ResearchUserFollowingData {
     pub has_more: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl CursorPage for ResearchUserFollowingData {
+    type Item = ResearchUserInfo;
+
+    fn cursor(&self) -> i64 {
+        self.cursor
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    fn into_items(self) -> Vec<ResearchUserInfo> {
+        self.user_following
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ResearchRepostedVideosRequest {
     pub username: String,
     pub max_count: Option<i64>,
@@ -141,7 +193,23 @@ pub struct ResearchRepostedVideosData {
     pub has_more: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl CursorPage for ResearchRepostedVideosData {
+    type Item = ResearchVideo;
+
+    fn cursor(&self) -> i64 {
+        self.cursor
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    fn into_items(self) -> Vec<ResearchVideo> {
+        self.user_reposted_videos
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum ResearchVideoField {
     Id,
@@ -181,9 +249,9 @@ impl ResearchVideoField {
     }
 }
 
-impl ToString for ResearchVideoField {
-    fn to_string(&self) -> String {
-        self.as_str().to_string()
+impl fmt::Display for ResearchVideoField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -195,12 +263,29 @@ pub struct ResearchCondition {
     pub field_values: Vec<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, Builder)]
-#[builder(setter(into, strip_option))]
+/// An entry of a `ResearchQuery` list: either a single condition or a nested query.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ResearchClause {
+    Condition(ResearchCondition),
+    Group(ResearchQuery),
+}
+
+/// A research query: every `and` clause must match, at least one `or` clause must match and no
+/// `not` clause may match.
+///
+/// Unknown fields are rejected, so a malformed condition in a clause list fails to parse
+/// instead of being read as an empty query matching everything.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, Builder)]
+#[builder(setter(into, strip_option), default)]
+#[serde(deny_unknown_fields)]
 pub struct ResearchQuery {
-    pub and: Option<Vec<ResearchCondition>>,
-    pub or: Option<Vec<ResearchCondition>>,
-    pub not: Option<Vec<ResearchCondition>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub and: Option<Vec<ResearchClause>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub or: Option<Vec<ResearchClause>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not: Option<Vec<ResearchClause>>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Builder)]
@@ -215,6 +300,60 @@ pub struct QueryRequest {
     pub is_random: Option<bool>,
 }
 
+/// Longest date range, in days, TikTok accepts in a single `QueryRequest`.
+pub const MAX_QUERY_WINDOW_DAYS: i64 = 30;
+
+/// An inclusive date range accepted by the research video query endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateWindow {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateWindow {
+    /// Returns the start date in the `YYYYMMDD` format expected by TikTok.
+    pub fn start_date(&self) -> String {
+        self.start.format("%Y%m%d").to_string()
+    }
+
+    /// Returns the end date in the `YYYYMMDD` format expected by TikTok.
+    pub fn end_date(&self) -> String {
+        self.end.format("%Y%m%d").to_string()
+    }
+}
+
+/// Splits the inclusive range `from..=to` into consecutive windows of at most
+/// `MAX_QUERY_WINDOW_DAYS` days. Returns no windows when `from` is after `to`.
+pub fn date_windows(from: NaiveDate, to: NaiveDate) -> Vec<DateWindow> {
+    let mut windows = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let end = (start + Duration::days(MAX_QUERY_WINDOW_DAYS - 1)).min(to);
+        windows.push(DateWindow { start, end });
+        start = end + Duration::days(1);
+    }
+    windows
+}
+
+/// Progress of a `Service::query_videos_range_with_progress` crawl.
+#[derive(Clone, Debug)]
+pub struct DateWindowProgress {
+    /// Zero-based index of the window.
+    pub index: usize,
+    /// Number of windows the range was split into.
+    pub total: usize,
+    pub window: DateWindow,
+    pub status: DateWindowStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DateWindowStatus {
+    /// The first page of the window is about to be requested.
+    Started,
+    /// All pages of the window were fetched.
+    Completed { videos: usize },
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 pub struct ResearchVideo {
     #[serde(default)]
@@ -265,6 +404,26 @@ pub struct ResearchQueryVideoResponseData {
     pub search_id: Option<String>,
 }
 
+impl CursorPage for ResearchQueryVideoResponseData {
+    type Item = ResearchVideo;
+
+    fn cursor(&self) -> i64 {
+        self.cursor
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    fn search_id(&self) -> Option<&str> {
+        self.search_id.as_deref()
+    }
+
+    fn into_items(self) -> Vec<ResearchVideo> {
+        self.videos
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ResearchQueryVideoResponse {
     #[serde(default)]
@@ -521,7 +680,260 @@ pub enum ResearchRegionCode {
     RU,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl ResearchRegionCode {
+    /// Returns the ISO 3166-1 alpha-2 code TikTok expects.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResearchRegionCode::FR => "FR",
+            ResearchRegionCode::TH => "TH",
+            ResearchRegionCode::MM => "MM",
+            ResearchRegionCode::BD => "BD",
+            ResearchRegionCode::IT => "IT",
+            ResearchRegionCode::NP => "NP",
+            ResearchRegionCode::IQ => "IQ",
+            ResearchRegionCode::BR => "BR",
+            ResearchRegionCode::US => "US",
+            ResearchRegionCode::KW => "KW",
+            ResearchRegionCode::VN => "VN",
+            ResearchRegionCode::AR => "AR",
+            ResearchRegionCode::KZ => "KZ",
+            ResearchRegionCode::GB => "GB",
+            ResearchRegionCode::UA => "UA",
+            ResearchRegionCode::TR => "TR",
+            ResearchRegionCode::ID => "ID",
+            ResearchRegionCode::PK => "PK",
+            ResearchRegionCode::NG => "NG",
+            ResearchRegionCode::KH => "KH",
+            ResearchRegionCode::PH => "PH",
+            ResearchRegionCode::EG => "EG",
+            ResearchRegionCode::QA => "QA",
+            ResearchRegionCode::MY => "MY",
+            ResearchRegionCode::ES => "ES",
+            ResearchRegionCode::JO => "JO",
+            ResearchRegionCode::MA => "MA",
+            ResearchRegionCode::SA => "SA",
+            ResearchRegionCode::TW => "TW",
+            ResearchRegionCode::AF => "AF",
+            ResearchRegionCode::EC => "EC",
+            ResearchRegionCode::MX => "MX",
+            ResearchRegionCode::BW => "BW",
+            ResearchRegionCode::JP => "JP",
+            ResearchRegionCode::LT => "LT",
+            ResearchRegionCode::TN => "TN",
+            ResearchRegionCode::RO => "RO",
+            ResearchRegionCode::LY => "LY",
+            ResearchRegionCode::IL => "IL",
+            ResearchRegionCode::DZ => "DZ",
+            ResearchRegionCode::CG => "CG",
+            ResearchRegionCode::GH => "GH",
+            ResearchRegionCode::DE => "DE",
+            ResearchRegionCode::BJ => "BJ",
+            ResearchRegionCode::SN => "SN",
+            ResearchRegionCode::SK => "SK",
+            ResearchRegionCode::BY => "BY",
+            ResearchRegionCode::NL => "NL",
+            ResearchRegionCode::LA => "LA",
+            ResearchRegionCode::BE => "BE",
+            ResearchRegionCode::DO => "DO",
+            ResearchRegionCode::TZ => "TZ",
+            ResearchRegionCode::LK => "LK",
+            ResearchRegionCode::NI => "NI",
+            ResearchRegionCode::LB => "LB",
+            ResearchRegionCode::IE => "IE",
+            ResearchRegionCode::RS => "RS",
+            ResearchRegionCode::HU => "HU",
+            ResearchRegionCode::PT => "PT",
+            ResearchRegionCode::GP => "GP",
+            ResearchRegionCode::CM => "CM",
+            ResearchRegionCode::HN => "HN",
+            ResearchRegionCode::FI => "FI",
+            ResearchRegionCode::GA => "GA",
+            ResearchRegionCode::BN => "BN",
+            ResearchRegionCode::SG => "SG",
+            ResearchRegionCode::BO => "BO",
+            ResearchRegionCode::GM => "GM",
+            ResearchRegionCode::BG => "BG",
+            ResearchRegionCode::SD => "SD",
+            ResearchRegionCode::TT => "TT",
+            ResearchRegionCode::OM => "OM",
+            ResearchRegionCode::FO => "FO",
+            ResearchRegionCode::MZ => "MZ",
+            ResearchRegionCode::ML => "ML",
+            ResearchRegionCode::UG => "UG",
+            ResearchRegionCode::RE => "RE",
+            ResearchRegionCode::PY => "PY",
+            ResearchRegionCode::GT => "GT",
+            ResearchRegionCode::CI => "CI",
+            ResearchRegionCode::SR => "SR",
+            ResearchRegionCode::AO => "AO",
+            ResearchRegionCode::AZ => "AZ",
+            ResearchRegionCode::LR => "LR",
+            ResearchRegionCode::CD => "CD",
+            ResearchRegionCode::HR => "HR",
+            ResearchRegionCode::SV => "SV",
+            ResearchRegionCode::MV => "MV",
+            ResearchRegionCode::GY => "GY",
+            ResearchRegionCode::BH => "BH",
+            ResearchRegionCode::TG => "TG",
+            ResearchRegionCode::SL => "SL",
+            ResearchRegionCode::MK => "MK",
+            ResearchRegionCode::KE => "KE",
+            ResearchRegionCode::MT => "MT",
+            ResearchRegionCode::MG => "MG",
+            ResearchRegionCode::MR => "MR",
+            ResearchRegionCode::PA => "PA",
+            ResearchRegionCode::IS => "IS",
+            ResearchRegionCode::LU => "LU",
+            ResearchRegionCode::HT => "HT",
+            ResearchRegionCode::TM => "TM",
+            ResearchRegionCode::ZM => "ZM",
+            ResearchRegionCode::CR => "CR",
+            ResearchRegionCode::NO => "NO",
+            ResearchRegionCode::AL => "AL",
+            ResearchRegionCode::ET => "ET",
+            ResearchRegionCode::GW => "GW",
+            ResearchRegionCode::AU => "AU",
+            ResearchRegionCode::KR => "KR",
+            ResearchRegionCode::UY => "UY",
+            ResearchRegionCode::JM => "JM",
+            ResearchRegionCode::DK => "DK",
+            ResearchRegionCode::AE => "AE",
+            ResearchRegionCode::MD => "MD",
+            ResearchRegionCode::SE => "SE",
+            ResearchRegionCode::MU => "MU",
+            ResearchRegionCode::SO => "SO",
+            ResearchRegionCode::CO => "CO",
+            ResearchRegionCode::AT => "AT",
+            ResearchRegionCode::GR => "GR",
+            ResearchRegionCode::UZ => "UZ",
+            ResearchRegionCode::CL => "CL",
+            ResearchRegionCode::GE => "GE",
+            ResearchRegionCode::PL => "PL",
+            ResearchRegionCode::CA => "CA",
+            ResearchRegionCode::CZ => "CZ",
+            ResearchRegionCode::ZA => "ZA",
+            ResearchRegionCode::AI => "AI",
+            ResearchRegionCode::VE => "VE",
+            ResearchRegionCode::KG => "KG",
+            ResearchRegionCode::PE => "PE",
+            ResearchRegionCode::CH => "CH",
+            ResearchRegionCode::LV => "LV",
+            ResearchRegionCode::PR => "PR",
+            ResearchRegionCode::NZ => "NZ",
+            ResearchRegionCode::TL => "TL",
+            ResearchRegionCode::BT => "BT",
+            ResearchRegionCode::MN => "MN",
+            ResearchRegionCode::FJ => "FJ",
+            ResearchRegionCode::SZ => "SZ",
+            ResearchRegionCode::VU => "VU",
+            ResearchRegionCode::BF => "BF",
+            ResearchRegionCode::TJ => "TJ",
+            ResearchRegionCode::BA => "BA",
+            ResearchRegionCode::AM => "AM",
+            ResearchRegionCode::TD => "TD",
+            ResearchRegionCode::SI => "SI",
+            ResearchRegionCode::CY => "CY",
+            ResearchRegionCode::MW => "MW",
+            ResearchRegionCode::EE => "EE",
+            ResearchRegionCode::XK => "XK",
+            ResearchRegionCode::ME => "ME",
+            ResearchRegionCode::KY => "KY",
+            ResearchRegionCode::YE => "YE",
+            ResearchRegionCode::LS => "LS",
+            ResearchRegionCode::ZW => "ZW",
+            ResearchRegionCode::MC => "MC",
+            ResearchRegionCode::GN => "GN",
+            ResearchRegionCode::BS => "BS",
+            ResearchRegionCode::PF => "PF",
+            ResearchRegionCode::NA => "NA",
+            ResearchRegionCode::VI => "VI",
+            ResearchRegionCode::BB => "BB",
+            ResearchRegionCode::BZ => "BZ",
+            ResearchRegionCode::CW => "CW",
+            ResearchRegionCode::PS => "PS",
+            ResearchRegionCode::FM => "FM",
+            ResearchRegionCode::PG => "PG",
+            ResearchRegionCode::BI => "BI",
+            ResearchRegionCode::AD => "AD",
+            ResearchRegionCode::TV => "TV",
+            ResearchRegionCode::GL => "GL",
+            ResearchRegionCode::KM => "KM",
+            ResearchRegionCode::AW => "AW",
+            ResearchRegionCode::TC => "TC",
+            ResearchRegionCode::CV => "CV",
+            ResearchRegionCode::MO => "MO",
+            ResearchRegionCode::VC => "VC",
+            ResearchRegionCode::NE => "NE",
+            ResearchRegionCode::WS => "WS",
+            ResearchRegionCode::MP => "MP",
+            ResearchRegionCode::DJ => "DJ",
+            ResearchRegionCode::RW => "RW",
+            ResearchRegionCode::AG => "AG",
+            ResearchRegionCode::GI => "GI",
+            ResearchRegionCode::GQ => "GQ",
+            ResearchRegionCode::AS => "AS",
+            ResearchRegionCode::AX => "AX",
+            ResearchRegionCode::TO => "TO",
+            ResearchRegionCode::KN => "KN",
+            ResearchRegionCode::LC => "LC",
+            ResearchRegionCode::NC => "NC",
+            ResearchRegionCode::LI => "LI",
+            ResearchRegionCode::SS => "SS",
+            ResearchRegionCode::IR => "IR",
+            ResearchRegionCode::SY => "SY",
+            ResearchRegionCode::IM => "IM",
+            ResearchRegionCode::SC => "SC",
+            ResearchRegionCode::VG => "VG",
+            ResearchRegionCode::SB => "SB",
+            ResearchRegionCode::DM => "DM",
+            ResearchRegionCode::KI => "KI",
+            ResearchRegionCode::UM => "UM",
+            ResearchRegionCode::SX => "SX",
+            ResearchRegionCode::GD => "GD",
+            ResearchRegionCode::MH => "MH",
+            ResearchRegionCode::BQ => "BQ",
+            ResearchRegionCode::YT => "YT",
+            ResearchRegionCode::ST => "ST",
+            ResearchRegionCode::CF => "CF",
+            ResearchRegionCode::BM => "BM",
+            ResearchRegionCode::SM => "SM",
+            ResearchRegionCode::PW => "PW",
+            ResearchRegionCode::GU => "GU",
+            ResearchRegionCode::HK => "HK",
+            ResearchRegionCode::IN => "IN",
+            ResearchRegionCode::CK => "CK",
+            ResearchRegionCode::AQ => "AQ",
+            ResearchRegionCode::WF => "WF",
+            ResearchRegionCode::JE => "JE",
+            ResearchRegionCode::MQ => "MQ",
+            ResearchRegionCode::CN => "CN",
+            ResearchRegionCode::GF => "GF",
+            ResearchRegionCode::MS => "MS",
+            ResearchRegionCode::GG => "GG",
+            ResearchRegionCode::TK => "TK",
+            ResearchRegionCode::FK => "FK",
+            ResearchRegionCode::PM => "PM",
+            ResearchRegionCode::NU => "NU",
+            ResearchRegionCode::MF => "MF",
+            ResearchRegionCode::ER => "ER",
+            ResearchRegionCode::NF => "NF",
+            ResearchRegionCode::VA => "VA",
+            ResearchRegionCode::IO => "IO",
+            ResearchRegionCode::SH => "SH",
+            ResearchRegionCode::BL => "BL",
+            ResearchRegionCode::CU => "CU",
+            ResearchRegionCode::NR => "NR",
+            ResearchRegionCode::TP => "TP",
+            ResearchRegionCode::BV => "BV",
+            ResearchRegionCode::EH => "EH",
+            ResearchRegionCode::PN => "PN",
+            ResearchRegionCode::TF => "TF",
+            ResearchRegionCode::RU => "RU",
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ResearchVideoCommentsRequest {
     pub video_id: i64,
     pub max_count: Option<i64>,
@@ -541,6 +953,22 @@ pub struct ResearchVideoCommentsData {
     pub has_more: bool,
 }
 
+impl CursorPage for ResearchVideoCommentsData {
+    type Item = ResearchCommentObject;
+
+    fn cursor(&self) -> i64 {
+        self.cursor
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    fn into_items(self) -> Vec<ResearchCommentObject> {
+        self.comments
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ResearchCommentObject {
     pub id: i64,
@@ -553,9 +981,25 @@ pub struct ResearchCommentObject {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_date_windows_split_long_ranges() {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let windows = date_windows(from, to);
+
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0].start_date(), "20240101");
+        assert_eq!(windows[0].end_date(), "20240130");
+        assert_eq!(windows[1].start_date(), "20240131");
+        assert_eq!(windows[2].end_date(), "20240315");
+        assert!(date_windows(to, from).is_empty());
+        assert_eq!(date_windows(from, from).len(), 1);
+    }
+
     #[test]
     fn test_deserialize_with_empty_data() {
         let json_data = r#"{"data":{},"error":{"code":"access_token_invalid","message":"The access token is invalid or not found in the request.","log_id":"20240829190020EBE0D84CCEA6DE000CB6"}}"#;
@@ -0,0 +1,600 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{BitAnd, BitOr, Not};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::{ResearchClause, ResearchCondition, ResearchQuery, ResearchRegionCode};
+
+/// Fields the research video query endpoint can filter on.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionField {
+    CreateDate,
+    Username,
+    RegionCode,
+    VideoId,
+    HashtagName,
+    Keyword,
+    MusicId,
+    EffectId,
+    VideoLength,
+}
+
+impl ConditionField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConditionField::CreateDate => "create_date",
+            ConditionField::Username => "username",
+            ConditionField::RegionCode => "region_code",
+            ConditionField::VideoId => "video_id",
+            ConditionField::HashtagName => "hashtag_name",
+            ConditionField::Keyword => "keyword",
+            ConditionField::MusicId => "music_id",
+            ConditionField::EffectId => "effect_id",
+            ConditionField::VideoLength => "video_length",
+        }
+    }
+}
+
+/// Comparison operations supported by the research video query endpoint.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Operation {
+    Eq,
+    In,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl Operation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Eq => "EQ",
+            Operation::In => "IN",
+            Operation::Gt => "GT",
+            Operation::Gte => "GTE",
+            Operation::Lt => "LT",
+            Operation::Lte => "LTE",
+        }
+    }
+}
+
+/// Duration buckets accepted by the `video_length` condition.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VideoLength {
+    /// Under 15 seconds.
+    Short,
+    /// Between 15 and 60 seconds.
+    Mid,
+    /// Between 1 and 5 minutes.
+    Long,
+    /// Over 5 minutes.
+    ExtraLong,
+}
+
+impl VideoLength {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoLength::Short => "SHORT",
+            VideoLength::Mid => "MID",
+            VideoLength::Long => "LONG",
+            VideoLength::ExtraLong => "EXTRA_LONG",
+        }
+    }
+}
+
+/// A value that can be used in a research query condition.
+pub trait ConditionValue {
+    /// Formats the value the way TikTok expects it in `field_values`.
+    fn to_condition_value(&self) -> String;
+}
+
+impl ConditionValue for String {
+    fn to_condition_value(&self) -> String {
+        self.clone()
+    }
+}
+
+impl ConditionValue for i64 {
+    fn to_condition_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl ConditionValue for NaiveDate {
+    fn to_condition_value(&self) -> String {
+        self.format("%Y%m%d").to_string()
+    }
+}
+
+impl ConditionValue for ResearchRegionCode {
+    fn to_condition_value(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+impl ConditionValue for VideoLength {
+    fn to_condition_value(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+impl ResearchCondition {
+    /// Creates a condition from typed parts.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The field to filter on.
+    /// * `operation` - The comparison operation.
+    /// * `values` - The values to compare against.
+    pub fn new<T: ConditionValue>(
+        field: ConditionField,
+        operation: Operation,
+        values: impl IntoIterator<Item = T>,
+    ) -> Self {
+        Self {
+            field_name: field.as_str().to_string(),
+            operation: operation.as_str().to_string(),
+            field_values: values.into_iter().map(|v| v.to_condition_value()).collect(),
+        }
+    }
+}
+
+/// A condition field whose values must be of type `T`.
+///
+/// Created through `Cond`, so only fields and operations TikTok supports can be expressed.
+#[derive(Clone, Copy, Debug)]
+pub struct Field<T> {
+    field: ConditionField,
+    value: PhantomData<T>,
+}
+
+impl<T: ConditionValue> Field<T> {
+    fn new(field: ConditionField) -> Self {
+        Self {
+            field,
+            value: PhantomData,
+        }
+    }
+
+    /// Matches videos whose field equals `value`.
+    pub fn eq(self, value: impl Into<T>) -> ResearchCondition {
+        ResearchCondition::new(self.field, Operation::Eq, [value.into()])
+    }
+
+    /// Matches videos whose field equals any of `values`.
+    pub fn is_in<V: Into<T>>(self, values: impl IntoIterator<Item = V>) -> ResearchCondition {
+        ResearchCondition::new(
+            self.field,
+            Operation::In,
+            values.into_iter().map(Into::into),
+        )
+    }
+}
+
+impl Field<NaiveDate> {
+    /// Matches videos created after `date`.
+    pub fn gt(self, date: NaiveDate) -> ResearchCondition {
+        ResearchCondition::new(self.field, Operation::Gt, [date])
+    }
+
+    /// Matches videos created on or after `date`.
+    pub fn gte(self, date: NaiveDate) -> ResearchCondition {
+        ResearchCondition::new(self.field, Operation::Gte, [date])
+    }
+
+    /// Matches videos created before `date`.
+    pub fn lt(self, date: NaiveDate) -> ResearchCondition {
+        ResearchCondition::new(self.field, Operation::Lt, [date])
+    }
+
+    /// Matches videos created on or before `date`.
+    pub fn lte(self, date: NaiveDate) -> ResearchCondition {
+        ResearchCondition::new(self.field, Operation::Lte, [date])
+    }
+}
+
+/// Entry point of the typed research query conditions.
+///
+/// ```
+/// use tiktok_rust::research::{Cond, ResearchRegionCode::{GB, US}};
+///
+/// let by_region = Cond::region_code().is_in([US, GB]);
+/// let by_hashtag = Cond::hashtag_name().eq("rust");
+/// ```
+pub struct Cond;
+
+impl Cond {
+    pub fn create_date() -> Field<NaiveDate> {
+        Field::new(ConditionField::CreateDate)
+    }
+
+    pub fn username() -> Field<String> {
+        Field::new(ConditionField::Username)
+    }
+
+    pub fn region_code() -> Field<ResearchRegionCode> {
+        Field::new(ConditionField::RegionCode)
+    }
+
+    pub fn video_id() -> Field<i64> {
+        Field::new(ConditionField::VideoId)
+    }
+
+    pub fn hashtag_name() -> Field<String> {
+        Field::new(ConditionField::HashtagName)
+    }
+
+    pub fn keyword() -> Field<String> {
+        Field::new(ConditionField::Keyword)
+    }
+
+    pub fn music_id() -> Field<i64> {
+        Field::new(ConditionField::MusicId)
+    }
+
+    pub fn effect_id() -> Field<i64> {
+        Field::new(ConditionField::EffectId)
+    }
+
+    pub fn video_length() -> Field<VideoLength> {
+        Field::new(ConditionField::VideoLength)
+    }
+}
+
+/// Matches videos matching every clause.
+///
+/// Nested `and` groups are flattened into the resulting group.
+pub fn and<C: Into<ResearchClause>>(clauses: impl IntoIterator<Item = C>) -> ResearchClause {
+    let mut flattened = Vec::new();
+    for clause in clauses.into_iter().map(Into::into) {
+        match clause {
+            ResearchClause::Group(ResearchQuery {
+                and: Some(inner),
+                or: None,
+                not: None,
+            }) => flattened.extend(inner),
+            clause => flattened.push(clause),
+        }
+    }
+    ResearchClause::Group(ResearchQuery {
+        and: Some(flattened),
+        ..Default::default()
+    })
+}
+
+/// Matches videos matching at least one clause.
+///
+/// Nested `or` groups are flattened into the resulting group.
+pub fn or<C: Into<ResearchClause>>(clauses: impl IntoIterator<Item = C>) -> ResearchClause {
+    let mut flattened = Vec::new();
+    for clause in clauses.into_iter().map(Into::into) {
+        match clause {
+            ResearchClause::Group(ResearchQuery {
+                and: None,
+                or: Some(inner),
+                not: None,
+            }) => flattened.extend(inner),
+            clause => flattened.push(clause),
+        }
+    }
+    ResearchClause::Group(ResearchQuery {
+        or: Some(flattened),
+        ..Default::default()
+    })
+}
+
+/// Matches videos not matching the clause.
+pub fn not(clause: impl Into<ResearchClause>) -> ResearchClause {
+    ResearchClause::Group(ResearchQuery {
+        not: Some(vec![clause.into()]),
+        ..Default::default()
+    })
+}
+
+impl From<ResearchCondition> for ResearchClause {
+    fn from(condition: ResearchCondition) -> Self {
+        ResearchClause::Condition(condition)
+    }
+}
+
+impl From<ResearchQuery> for ResearchClause {
+    fn from(query: ResearchQuery) -> Self {
+        ResearchClause::Group(query)
+    }
+}
+
+impl From<ResearchClause> for ResearchQuery {
+    fn from(clause: ResearchClause) -> Self {
+        match clause {
+            ResearchClause::Group(query) => query,
+            condition => ResearchQuery {
+                and: Some(vec![condition]),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl From<ResearchCondition> for ResearchQuery {
+    fn from(condition: ResearchCondition) -> Self {
+        ResearchClause::from(condition).into()
+    }
+}
+
+macro_rules! impl_boolean_ops {
+    ($($ty:ty),*) => {$(
+        impl<R: Into<ResearchClause>> BitAnd<R> for $ty {
+            type Output = ResearchClause;
+
+            fn bitand(self, rhs: R) -> ResearchClause {
+                and([ResearchClause::from(self), rhs.into()])
+            }
+        }
+
+        impl<R: Into<ResearchClause>> BitOr<R> for $ty {
+            type Output = ResearchClause;
+
+            fn bitor(self, rhs: R) -> ResearchClause {
+                or([ResearchClause::from(self), rhs.into()])
+            }
+        }
+
+        impl Not for $ty {
+            type Output = ResearchClause;
+
+            fn not(self) -> ResearchClause {
+                not(self)
+            }
+        }
+    )*};
+}
+
+impl_boolean_ops!(ResearchCondition, ResearchClause, ResearchQuery);
+
+impl fmt::Display for ResearchCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = match self.operation.as_str() {
+            "EQ" => "=",
+            "GT" => ">",
+            "GTE" => ">=",
+            "LT" => "<",
+            "LTE" => "<=",
+            other => other,
+        };
+        if self.operation == "IN" {
+            write!(
+                f,
+                "{} IN ({})",
+                self.field_name,
+                self.field_values.join(", ")
+            )
+        } else {
+            write!(
+                f,
+                "{} {} {}",
+                self.field_name,
+                operator,
+                self.field_values.join(", ")
+            )
+        }
+    }
+}
+
+/// A top-level term of a rendered query.
+struct Term {
+    text: String,
+    is_alternative: bool,
+}
+
+impl ResearchQuery {
+    /// Returns the rendered top-level terms, which are combined with AND.
+    fn terms(&self) -> Vec<Term> {
+        let mut terms: Vec<Term> = self
+            .and
+            .iter()
+            .flatten()
+            .map(|clause| Term {
+                text: clause.to_nested_string(),
+                is_alternative: false,
+            })
+            .collect();
+
+        if let Some(clauses) = self.or.as_ref().filter(|clauses| !clauses.is_empty()) {
+            let alternatives: Vec<_> = clauses
+                .iter()
+                .map(ResearchClause::to_nested_string)
+                .collect();
+            terms.push(Term {
+                text: alternatives.join(" OR "),
+                is_alternative: clauses.len() > 1,
+            });
+        }
+
+        terms.extend(self.not.iter().flatten().map(|clause| Term {
+            text: format!("NOT {}", clause.to_nested_string()),
+            is_alternative: false,
+        }));
+        terms
+    }
+}
+
+impl ResearchClause {
+    /// Renders the clause, parenthesized when it combines several terms.
+    fn to_nested_string(&self) -> String {
+        match self {
+            ResearchClause::Condition(condition) => condition.to_string(),
+            ResearchClause::Group(query) => {
+                let terms = query.terms();
+                match terms.as_slice() {
+                    [term] if !term.is_alternative => term.text.clone(),
+                    _ => format!("({})", query),
+                }
+            }
+        }
+    }
+}
+
+/// Renders the query as human readable text, e.g.
+/// `region_code IN (US, GB) AND (hashtag_name = rust OR keyword = rust) AND NOT username = spam`.
+impl fmt::Display for ResearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms = self.terms();
+        match terms.as_slice() {
+            [] => f.write_str("*"),
+            [term] => f.write_str(&term.text),
+            terms => {
+                let rendered: Vec<_> = terms
+                    .iter()
+                    .map(|term| {
+                        if term.is_alternative {
+                            format!("({})", term.text)
+                        } else {
+                            term.text.clone()
+                        }
+                    })
+                    .collect();
+                f.write_str(&rendered.join(" AND "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ResearchClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResearchClause::Condition(condition) => condition.fmt(f),
+            ResearchClause::Group(query) => query.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::research::ResearchRegionCode::{GB, US};
+
+    #[test]
+    fn test_conditions_serialize_to_research_condition_json() {
+        let condition = Cond::region_code().is_in([US, GB]);
+        assert_eq!(
+            serde_json::to_value(condition).unwrap(),
+            json!({"field_name": "region_code", "operation": "IN", "field_values": ["US", "GB"]})
+        );
+
+        let condition = Cond::hashtag_name().eq("rust");
+        assert_eq!(
+            serde_json::to_value(condition).unwrap(),
+            json!({"field_name": "hashtag_name", "operation": "EQ", "field_values": ["rust"]})
+        );
+    }
+
+    #[test]
+    fn test_region_codes_match_their_serde_representation() {
+        for region in [US, GB, ResearchRegionCode::FR, ResearchRegionCode::ZA] {
+            assert_eq!(
+                serde_json::to_value(&region).unwrap(),
+                json!(region.as_str())
+            );
+            assert_eq!(region.to_condition_value(), region.as_str());
+        }
+    }
+
+    #[test]
+    fn test_typed_values_are_formatted() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 9).unwrap();
+        let condition = Cond::create_date().gte(date);
+        assert_eq!(condition.field_name, "create_date");
+        assert_eq!(condition.operation, "GTE");
+        assert_eq!(condition.field_values, ["20240209"]);
+
+        let condition = Cond::video_length().is_in([VideoLength::Short, VideoLength::ExtraLong]);
+        assert_eq!(condition.field_values, ["SHORT", "EXTRA_LONG"]);
+
+        let condition = Cond::music_id().eq(42);
+        assert_eq!(condition.field_values, ["42"]);
+    }
+
+    #[test]
+    fn test_nested_groups_serialize_to_nested_json() {
+        let a = Cond::region_code().eq(US);
+        let b = Cond::region_code().eq(GB);
+        let c = Cond::hashtag_name().eq("spam");
+        let query = ResearchQuery::from(and([or([a, b]), not(c)]));
+
+        assert_eq!(
+            serde_json::to_value(&query).unwrap(),
+            json!({
+                "and": [
+                    {"or": [
+                        {"field_name": "region_code", "operation": "EQ", "field_values": ["US"]},
+                        {"field_name": "region_code", "operation": "EQ", "field_values": ["GB"]}
+                    ]},
+                    {"not": [
+                        {"field_name": "hashtag_name", "operation": "EQ", "field_values": ["spam"]}
+                    ]}
+                ]
+            })
+        );
+
+        let parsed: ResearchQuery =
+            serde_json::from_value(serde_json::to_value(&query).unwrap()).unwrap();
+        assert_eq!(parsed.to_string(), query.to_string());
+    }
+
+    #[test]
+    fn test_malformed_conditions_fail_to_parse() {
+        let misspelled = json!({"and": [
+            {"field_nme": "region_code", "operation": "EQ", "field_values": ["US"]}
+        ]});
+        assert!(serde_json::from_value::<ResearchQuery>(misspelled).is_err());
+
+        let incomplete = json!({"or": [{"field_name": "region_code", "operation": "EQ"}]});
+        assert!(serde_json::from_value::<ResearchQuery>(incomplete).is_err());
+
+        let nested = json!({"and": [{"not": [
+            {"field_name": "hashtag_name", "operation": "EQ", "field_values": ["spam"]}
+        ]}]});
+        let query: ResearchQuery = serde_json::from_value(nested).unwrap();
+        assert_eq!(query.to_string(), "NOT hashtag_name = spam");
+    }
+
+    #[test]
+    fn test_operators_build_flattened_groups() {
+        let query = ResearchQuery::from(
+            Cond::region_code().is_in([US, GB])
+                & (Cond::hashtag_name().eq("rust") | Cond::keyword().eq("rust"))
+                & !Cond::username().eq("spam"),
+        );
+
+        assert_eq!(query.and.as_ref().unwrap().len(), 3);
+        assert_eq!(
+            query.to_string(),
+            "region_code IN (US, GB) AND (hashtag_name = rust OR keyword = rust) AND NOT username = spam"
+        );
+    }
+
+    #[test]
+    fn test_display_of_top_level_lists() {
+        let query = ResearchQuery {
+            or: Some(vec![
+                Cond::keyword().eq("a").into(),
+                Cond::keyword().eq("b") & Cond::keyword().eq("c"),
+            ]),
+            not: Some(vec![Cond::keyword().eq("d").into()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            query.to_string(),
+            "(keyword = a OR (keyword = b AND keyword = c)) AND NOT keyword = d"
+        );
+        assert_eq!(ResearchQuery::default().to_string(), "*");
+    }
+}
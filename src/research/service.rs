@@ -1,31 +1,65 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use futures::stream::{self, BoxStream};
+use futures::{Stream, StreamExt};
+use reqwest::{RequestBuilder, StatusCode};
+
+use crate::api::{decode_envelope, TikTokClient};
+use crate::auth::TikTokScope;
+use crate::client::TokenCache;
 use crate::error::TikTokApiError;
-use reqwest::Client;
+use crate::pagination::{paginate, PageCursor};
 
 use super::{
-    QueryRequest, ResearchLikedVideosRequest, ResearchLikedVideosResponse,
-    ResearchPinnedVideosData, ResearchPinnedVideosRequest, ResearchPinnedVideosResponse,
-    ResearchQueryVideoResponse, ResearchQueryVideoResponseData, ResearchRepostedVideosData,
-    ResearchRepostedVideosRequest, ResearchRepostedVideosResponse, ResearchUserFollowerData,
-    ResearchUserFollowersRequest, ResearchUserFollowersResponse, ResearchUserFollowingData,
-    ResearchUserFollowingRequest, ResearchUserFollowingResponse, ResearchUserInfoData,
-    ResearchUserInfoRequest, ResearchUserInfoResponse, ResearchUserLikedVideosData,
+    date_windows, DateWindow, DateWindowProgress, DateWindowStatus, OwnedResearchAuth,
+    QueryRequest, ResearchAuth, ResearchCommentObject, ResearchLikedVideosRequest,
+    ResearchLikedVideosResponse, ResearchPinnedVideosData, ResearchPinnedVideosRequest,
+    ResearchPinnedVideosResponse, ResearchQuery, ResearchQueryVideoResponse,
+    ResearchQueryVideoResponseData, ResearchRepostedVideosData, ResearchRepostedVideosRequest,
+    ResearchRepostedVideosResponse, ResearchUserFollowerData, ResearchUserFollowersRequest,
+    ResearchUserFollowersResponse, ResearchUserFollowingData, ResearchUserFollowingRequest,
+    ResearchUserFollowingResponse, ResearchUserInfo, ResearchUserInfoData, ResearchUserInfoRequest,
+    ResearchUserInfoResponse, ResearchUserLikedVideosData, ResearchVideo,
     ResearchVideoCommentsData, ResearchVideoCommentsRequest, ResearchVideoCommentsResponse,
     ResearchVideoField,
 };
 
+#[derive(Clone)]
 pub struct Service {
-    base_url: String,
+    client: TikTokClient,
+    tokens: Option<TokenCache>,
+}
+
+impl Default for Service {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Service {
+    /// The scopes every research endpoint needs.
+    pub const REQUIRED_SCOPES: &'static [TikTokScope] = &[TikTokScope::ResearchDataBasic];
+
     /// Creates a new instance of the Service with the client key and secret from environment variables.
     ///
     /// # Panics
     ///
     /// Panics if the `TIKTOK_CLIENT_KEY` or `TIKTOK_CLIENT_SECRET` environment variables are not set.
     pub fn new() -> Self {
+        Self::from_client(TikTokClient::new())
+    }
+
+    /// Creates a new instance of the Service sharing the given `TikTokClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client whose connection pool and configuration are reused.
+    pub fn from_client(client: TikTokClient) -> Self {
         Self {
-            base_url: String::from("https://open.tiktokapis.com"),
+            client,
+            tokens: None,
         }
     }
 
@@ -35,9 +69,64 @@ impl Service {
     ///
     /// * `base_url` - A string slice that holds the custom base URL.
     pub fn with_base_url(mut self, base_url: &str) -> Self {
-        self.base_url = base_url.into();
+        self.client = self.client.with_base_url(base_url);
         self
     }
+
+    /// Authenticates calls made with `ResearchAuth::Cached` with a client access token from
+    /// `tokens`.
+    ///
+    /// The token is taken from the cache before each request, so long-running crawls keep
+    /// working after the token they started with expires. A request rejected with
+    /// `AccessTokenInvalid` is retried once with a newly fetched token.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - The cache providing client access tokens.
+    pub fn with_token_cache(mut self, tokens: TokenCache) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+
+    /// Returns the token cache, if any.
+    pub fn tokens(&self) -> Option<&TokenCache> {
+        self.tokens.as_ref()
+    }
+
+    /// Sends the request built by `build` for the token given by `auth`.
+    ///
+    /// Fails with `InvalidRequest` without sending anything if `auth` asks for a cached token
+    /// and no `TokenCache` is set.
+    async fn send<T>(
+        &self,
+        auth: ResearchAuth<'_>,
+        build: impl Fn(&str) -> RequestBuilder,
+        decode: impl Fn(StatusCode, &str) -> Result<T, TikTokApiError>,
+    ) -> Result<T, TikTokApiError> {
+        let tokens = match (auth, &self.tokens) {
+            (ResearchAuth::Token(token), _) => {
+                return self.client.execute(build(token.as_str()), decode).await
+            }
+            (ResearchAuth::Cached, Some(tokens)) => tokens,
+            (ResearchAuth::Cached, None) => {
+                return Err(TikTokApiError::InvalidRequest(
+                    "a cached token was requested but no token cache is set; \
+                     use Service::with_token_cache"
+                        .to_string(),
+                ))
+            }
+        };
+
+        let cached = tokens.access_token().await?;
+        match self.client.execute(build(&cached), &decode).await {
+            Err(TikTokApiError::AccessTokenInvalid(_)) => {
+                tokens.invalidate_token(&cached).await;
+                let fresh = tokens.access_token().await?;
+                self.client.execute(build(&fresh), &decode).await
+            }
+            result => result,
+        }
+    }
 }
 
 impl Service {
@@ -45,7 +134,7 @@ impl Service {
     ///
     /// # Arguments
     ///
-    /// * `token` - The client access token.
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
     /// * `fields` - A list of `VideoField` enums for the desired data.
     /// * `request` - A `UserInfoRequest` struct that holds the request parameters.
     ///
@@ -54,11 +143,13 @@ impl Service {
     /// A `Result` containing a `UserInfoData` on success, or a `TikTokApiError` on failure.
     pub async fn query_user_info(
         &self,
-        token: &str,
+        token: impl Into<ResearchAuth<'_>>,
         fields: &[ResearchVideoField],
         request: ResearchUserInfoRequest,
     ) -> Result<ResearchUserInfoData, TikTokApiError> {
-        let client = Client::new();
+        let token = token.into();
+        token.require(Self::REQUIRED_SCOPES)?;
+
         let fields_str = fields
             .iter()
             .map(|f| f.to_string())
@@ -66,39 +157,36 @@ impl Service {
             .join(",");
         let url = format!(
             "{}/v2/research/user/info/?fields={}",
-            self.base_url, fields_str
+            self.client.base_url(),
+            fields_str
         );
 
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let user_info_response: ResearchUserInfoResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-
-        if status.is_success() && user_info_response.error.code == "ok" {
-            Ok(user_info_response.data)
-        } else {
-            Err(TikTokApiError::from(user_info_response.error))
-        }
+        self.send(
+            token,
+            |token| {
+                self.client
+                    .http()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            },
+            |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |user_info_response: ResearchUserInfoResponse| user_info_response.data,
+                )
+            },
+        )
+        .await
     }
 
     /// Queries liked videos using the TikTok API.
     ///
     /// # Arguments
     ///
-    /// * `token` - The client access token.
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
     /// * `fields` - A list of `VideoField` enums for the desired data.
     /// * `request` - A `LikedVideosRequest` struct that holds the request parameters.
     ///
@@ -107,11 +195,13 @@ impl Service {
     /// A `Result` containing a `UserLikedVideosData` on success, or a `TikTokApiError` on failure.
     pub async fn query_liked_videos(
         &self,
-        token: &str,
+        token: impl Into<ResearchAuth<'_>>,
         fields: &[ResearchVideoField],
         request: ResearchLikedVideosRequest,
     ) -> Result<ResearchUserLikedVideosData, TikTokApiError> {
-        let client = Client::new();
+        let token = token.into();
+        token.require(Self::REQUIRED_SCOPES)?;
+
         let fields_str = fields
             .iter()
             .map(|f| f.to_string())
@@ -119,39 +209,36 @@ impl Service {
             .join(",");
         let url = format!(
             "{}/v2/research/user/liked_videos/?fields={}",
-            self.base_url, fields_str
+            self.client.base_url(),
+            fields_str
         );
 
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let liked_videos_response: ResearchLikedVideosResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-
-        if status.is_success() && liked_videos_response.error.code == "ok" {
-            Ok(liked_videos_response.data)
-        } else {
-            Err(TikTokApiError::from(liked_videos_response.error))
-        }
+        self.send(
+            token,
+            |token| {
+                self.client
+                    .http()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            },
+            |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |liked_videos_response: ResearchLikedVideosResponse| liked_videos_response.data,
+                )
+            },
+        )
+        .await
     }
 
     /// Queries pinned videos using the TikTok API.
     ///
     /// # Arguments
     ///
-    /// * `token` - The client access token.
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
     /// * `fields` - A list of `VideoField` enums for the desired data.
     /// * `request` - A `PinnedVideosRequest` struct that holds the request parameters.
     ///
@@ -160,11 +247,13 @@ impl Service {
     /// A `Result` containing a `PinnedVideosData` on success, or a `TikTokApiError` on failure.
     pub async fn query_pinned_videos(
         &self,
-        token: &str,
+        token: impl Into<ResearchAuth<'_>>,
         fields: &[ResearchVideoField],
         request: ResearchPinnedVideosRequest,
     ) -> Result<ResearchPinnedVideosData, TikTokApiError> {
-        let client = Client::new();
+        let token = token.into();
+        token.require(Self::REQUIRED_SCOPES)?;
+
         let fields_str = fields
             .iter()
             .map(|f| f.to_string())
@@ -172,39 +261,38 @@ impl Service {
             .join(",");
         let url = format!(
             "{}/v2/research/user/pinned_videos/?fields={}",
-            self.base_url, fields_str
+            self.client.base_url(),
+            fields_str
         );
 
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let pinned_videos_response: ResearchPinnedVideosResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-
-        if status.is_success() && pinned_videos_response.error.code == "ok" {
-            Ok(pinned_videos_response.data)
-        } else {
-            Err(TikTokApiError::from(pinned_videos_response.error))
-        }
+        self.send(
+            token,
+            |token| {
+                self.client
+                    .http()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            },
+            |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |pinned_videos_response: ResearchPinnedVideosResponse| {
+                        pinned_videos_response.data
+                    },
+                )
+            },
+        )
+        .await
     }
 
     /// Queries user followers using the TikTok API.
     ///
     /// # Arguments
     ///
-    /// * `token` - The client access token.
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
     /// * `request` - A `UserFollowersRequest` struct that holds the request parameters.
     ///
     /// # Returns
@@ -212,42 +300,42 @@ impl Service {
     /// A `Result` containing a `UserFollowerData` on success, or a `TikTokApiError` on failure.
     pub async fn query_user_followers(
         &self,
-        token: &str,
+        token: impl Into<ResearchAuth<'_>>,
         request: ResearchUserFollowersRequest,
     ) -> Result<ResearchUserFollowerData, TikTokApiError> {
-        let client = Client::new();
-        let url = format!("{}/v2/research/user/followers/", self.base_url);
-
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+        let token = token.into();
+        token.require(Self::REQUIRED_SCOPES)?;
 
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let user_followers_response: ResearchUserFollowersResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
+        let url = self.client.url("/v2/research/user/followers/");
 
-        if status.is_success() && user_followers_response.error.code == "ok" {
-            Ok(user_followers_response.data)
-        } else {
-            Err(TikTokApiError::from(user_followers_response.error))
-        }
+        self.send(
+            token,
+            |token| {
+                self.client
+                    .http()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            },
+            |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |user_followers_response: ResearchUserFollowersResponse| {
+                        user_followers_response.data
+                    },
+                )
+            },
+        )
+        .await
     }
 
     /// Queries user following using the TikTok API.
     ///
     /// # Arguments
     ///
-    /// * `token` - The client access token.
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
     /// * `request` - A `UserFollowingRequest` struct that holds the request parameters.
     ///
     /// # Returns
@@ -255,42 +343,42 @@ impl Service {
     /// A `Result` containing a `UserFollowingData` on success, or a `TikTokApiError` on failure.
     pub async fn query_user_following(
         &self,
-        token: &str,
+        token: impl Into<ResearchAuth<'_>>,
         request: ResearchUserFollowingRequest,
     ) -> Result<ResearchUserFollowingData, TikTokApiError> {
-        let client = Client::new();
-        let url = format!("{}/v2/research/user/following/", self.base_url);
-
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
+        let token = token.into();
+        token.require(Self::REQUIRED_SCOPES)?;
 
-        let user_following_response: ResearchUserFollowingResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
+        let url = self.client.url("/v2/research/user/following/");
 
-        if status.is_success() && user_following_response.error.code == "ok" {
-            Ok(user_following_response.data)
-        } else {
-            Err(TikTokApiError::from(user_following_response.error))
-        }
+        self.send(
+            token,
+            |token| {
+                self.client
+                    .http()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            },
+            |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |user_following_response: ResearchUserFollowingResponse| {
+                        user_following_response.data
+                    },
+                )
+            },
+        )
+        .await
     }
 
     /// Queries reposted videos using the TikTok API.
     ///
     /// # Arguments
     ///
-    /// * `token` - The client access token.
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
     /// * `fields` - A list of `VideoField` enums for the desired data.
     /// * `request` - A `RepostedVideosRequest` struct that holds the request parameters.
     ///
@@ -299,11 +387,13 @@ impl Service {
     /// A `Result` containing a `RepostedVideosData` on success, or a `TikTokApiError` on failure.
     pub async fn query_reposted_videos(
         &self,
-        token: &str,
+        token: impl Into<ResearchAuth<'_>>,
         fields: &[ResearchVideoField],
         request: ResearchRepostedVideosRequest,
     ) -> Result<ResearchRepostedVideosData, TikTokApiError> {
-        let client = Client::new();
+        let token = token.into();
+        token.require(Self::REQUIRED_SCOPES)?;
+
         let fields_str = fields
             .iter()
             .map(|f| f.to_string())
@@ -311,39 +401,38 @@ impl Service {
             .join(",");
         let url = format!(
             "{}/v2/research/user/reposted_videos/?fields={}",
-            self.base_url, fields_str
+            self.client.base_url(),
+            fields_str
         );
 
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let reposted_videos_response: ResearchRepostedVideosResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-
-        if status.is_success() && reposted_videos_response.error.code == "ok" {
-            Ok(reposted_videos_response.data)
-        } else {
-            Err(TikTokApiError::from(reposted_videos_response.error))
-        }
+        self.send(
+            token,
+            |token| {
+                self.client
+                    .http()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            },
+            |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |reposted_videos_response: ResearchRepostedVideosResponse| {
+                        reposted_videos_response.data
+                    },
+                )
+            },
+        )
+        .await
     }
 
     /// Queries videos using the TikTok API.
     ///
     /// # Arguments
     ///
-    /// * `token` - The API token.
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
     /// * `fields` - A list of `VideoField` enums for the desired data.
     /// * `request` - A `QueryRequest` struct that holds the query parameters.
     ///
@@ -352,11 +441,13 @@ impl Service {
     /// A `Result` containing a `QueryVideoResponseData` on success, or a `TikTokApiError` on failure.
     pub async fn query_videos(
         &self,
-        token: &str,
+        token: impl Into<ResearchAuth<'_>>,
         fields: &[ResearchVideoField],
         request: QueryRequest,
     ) -> Result<ResearchQueryVideoResponseData, TikTokApiError> {
-        let client = Client::new();
+        let token = token.into();
+        token.require(Self::REQUIRED_SCOPES)?;
+
         let fields_str = fields
             .iter()
             .map(|f| f.to_string())
@@ -364,39 +455,36 @@ impl Service {
             .join(",");
         let url = format!(
             "{}/v2/research/video/query/?fields={}",
-            self.base_url, fields_str
+            self.client.base_url(),
+            fields_str
         );
 
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let query_video_response: ResearchQueryVideoResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-
-        if status.is_success() && query_video_response.error.code == "ok" {
-            Ok(query_video_response.data)
-        } else {
-            Err(TikTokApiError::from(query_video_response.error))
-        }
+        self.send(
+            token,
+            |token| {
+                self.client
+                    .http()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            },
+            |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |query_video_response: ResearchQueryVideoResponse| query_video_response.data,
+                )
+            },
+        )
+        .await
     }
 
     /// Queries video comments using the TikTok API.
     ///
     /// # Arguments
     ///
-    /// * `token` - The API token.
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
     /// * `fields` - A comma-separated list of field names for the desired data.
     /// * `request` - A `VideoCommentsRequest` struct that holds the query parameters.
     ///
@@ -405,38 +493,716 @@ impl Service {
     /// A `Result` containing a `ResearchVideoCommentsData` on success, or a `TikTokApiError` on failure.
     pub async fn query_video_comments(
         &self,
-        token: &str,
+        token: impl Into<ResearchAuth<'_>>,
         fields: &str,
         request: ResearchVideoCommentsRequest,
     ) -> Result<ResearchVideoCommentsData, TikTokApiError> {
-        let client = Client::new();
+        let token = token.into();
+        token.require(Self::REQUIRED_SCOPES)?;
+
         let url = format!(
             "{}/v2/research/video/comment/list/?fields={}",
-            self.base_url, fields
+            self.client.base_url(),
+            fields
         );
 
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+        self.send(
+            token,
+            |token| {
+                self.client
+                    .http()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .json(&request)
+            },
+            |status, body| {
+                decode_envelope(
+                    status,
+                    body,
+                    |video_comments_response: ResearchVideoCommentsResponse| {
+                        video_comments_response.data
+                    },
+                )
+            },
+        )
+        .await
+    }
+}
+
+impl Service {
+    /// Streams all liked videos of a user using the TikTok API.
+    ///
+    /// Follows the pagination cursor, starting at `request.cursor`, until TikTok reports there
+    /// are no more results. The stream ends after yielding the first error.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
+    /// * `fields` - A list of `VideoField` enums for the desired data.
+    /// * `request` - A `ResearchLikedVideosRequest` struct that holds the request parameters.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of `ResearchVideo` results.
+    pub fn query_liked_videos_stream<'a>(
+        &self,
+        token: impl Into<ResearchAuth<'a>>,
+        fields: &[ResearchVideoField],
+        request: ResearchLikedVideosRequest,
+    ) -> impl Stream<Item = Result<ResearchVideo, TikTokApiError>> {
+        let service = self.clone();
+        let token = OwnedResearchAuth::from(token.into());
+        let fields = fields.to_vec();
+
+        paginate(PageCursor::new(request.cursor), move |page| {
+            let service = service.clone();
+            let token = token.clone();
+            let fields = fields.clone();
+            let request = ResearchLikedVideosRequest {
+                cursor: page.cursor,
+                ..request.clone()
+            };
+            async move {
+                service
+                    .query_liked_videos(token.as_auth(), &fields, request)
+                    .await
+            }
+        })
+    }
+
+    /// Streams all followers of a user using the TikTok API.
+    ///
+    /// Follows the pagination cursor, starting at `request.cursor`, until TikTok reports there
+    /// are no more results. The stream ends after yielding the first error.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
+    /// * `request` - A `ResearchUserFollowersRequest` struct that holds the request parameters.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of `ResearchUserInfo` results.
+    pub fn query_user_followers_stream<'a>(
+        &self,
+        token: impl Into<ResearchAuth<'a>>,
+        request: ResearchUserFollowersRequest,
+    ) -> impl Stream<Item = Result<ResearchUserInfo, TikTokApiError>> {
+        let service = self.clone();
+        let token = OwnedResearchAuth::from(token.into());
+
+        paginate(PageCursor::new(request.cursor), move |page| {
+            let service = service.clone();
+            let token = token.clone();
+            let request = ResearchUserFollowersRequest {
+                cursor: page.cursor,
+                ..request.clone()
+            };
+            async move { service.query_user_followers(token.as_auth(), request).await }
+        })
+    }
+
+    /// Streams all accounts followed by a user using the TikTok API.
+    ///
+    /// Follows the pagination cursor, starting at `request.cursor`, until TikTok reports there
+    /// are no more results. The stream ends after yielding the first error.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
+    /// * `request` - A `ResearchUserFollowingRequest` struct that holds the request parameters.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of `ResearchUserInfo` results.
+    pub fn query_user_following_stream<'a>(
+        &self,
+        token: impl Into<ResearchAuth<'a>>,
+        request: ResearchUserFollowingRequest,
+    ) -> impl Stream<Item = Result<ResearchUserInfo, TikTokApiError>> {
+        let service = self.clone();
+        let token = OwnedResearchAuth::from(token.into());
+
+        paginate(PageCursor::new(request.cursor), move |page| {
+            let service = service.clone();
+            let token = token.clone();
+            let request = ResearchUserFollowingRequest {
+                cursor: page.cursor,
+                ..request.clone()
+            };
+            async move { service.query_user_following(token.as_auth(), request).await }
+        })
+    }
+
+    /// Streams all reposted videos of a user using the TikTok API.
+    ///
+    /// Follows the pagination cursor, starting at `request.cursor`, until TikTok reports there
+    /// are no more results. The stream ends after yielding the first error.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
+    /// * `fields` - A list of `VideoField` enums for the desired data.
+    /// * `request` - A `ResearchRepostedVideosRequest` struct that holds the request parameters.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of `ResearchVideo` results.
+    pub fn query_reposted_videos_stream<'a>(
+        &self,
+        token: impl Into<ResearchAuth<'a>>,
+        fields: &[ResearchVideoField],
+        request: ResearchRepostedVideosRequest,
+    ) -> impl Stream<Item = Result<ResearchVideo, TikTokApiError>> {
+        let service = self.clone();
+        let token = OwnedResearchAuth::from(token.into());
+        let fields = fields.to_vec();
+
+        paginate(PageCursor::new(request.cursor), move |page| {
+            let service = service.clone();
+            let token = token.clone();
+            let fields = fields.clone();
+            let request = ResearchRepostedVideosRequest {
+                cursor: page.cursor,
+                ..request.clone()
+            };
+            async move {
+                service
+                    .query_reposted_videos(token.as_auth(), &fields, request)
+                    .await
+            }
+        })
+    }
+
+    /// Streams all comments of a video using the TikTok API.
+    ///
+    /// Follows the pagination cursor, starting at `request.cursor`, until TikTok reports there
+    /// are no more results. The stream ends after yielding the first error.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
+    /// * `fields` - A comma-separated list of field names for the desired data.
+    /// * `request` - A `ResearchVideoCommentsRequest` struct that holds the request parameters.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of `ResearchCommentObject` results.
+    pub fn query_video_comments_stream<'a>(
+        &self,
+        token: impl Into<ResearchAuth<'a>>,
+        fields: &str,
+        request: ResearchVideoCommentsRequest,
+    ) -> impl Stream<Item = Result<ResearchCommentObject, TikTokApiError>> {
+        let service = self.clone();
+        let token = OwnedResearchAuth::from(token.into());
+        let fields = fields.to_string();
+
+        paginate(PageCursor::new(request.cursor), move |page| {
+            let service = service.clone();
+            let token = token.clone();
+            let fields = fields.clone();
+            let request = ResearchVideoCommentsRequest {
+                cursor: page.cursor,
+                ..request.clone()
+            };
+            async move {
+                service
+                    .query_video_comments(token.as_auth(), &fields, request)
+                    .await
+            }
+        })
+    }
+
+    /// Streams all videos matching a query using the TikTok API.
+    ///
+    /// Follows the pagination cursor, starting at `request.cursor`, and sends the `search_id`
+    /// returned by TikTok with every following page as required. The stream ends after yielding
+    /// the first error.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
+    /// * `fields` - A list of `VideoField` enums for the desired data.
+    /// * `request` - A `QueryRequest` struct that holds the query parameters.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of `ResearchVideo` results.
+    pub fn query_videos_stream<'a>(
+        &self,
+        token: impl Into<ResearchAuth<'a>>,
+        fields: &[ResearchVideoField],
+        request: QueryRequest,
+    ) -> impl Stream<Item = Result<ResearchVideo, TikTokApiError>> {
+        self.videos_stream(
+            OwnedResearchAuth::from(token.into()),
+            fields.to_vec(),
+            request,
+        )
+    }
+
+    /// Streams the pages of `request`, keeping the token so the stream can outlive it.
+    fn videos_stream(
+        &self,
+        token: OwnedResearchAuth,
+        fields: Vec<ResearchVideoField>,
+        request: QueryRequest,
+    ) -> impl Stream<Item = Result<ResearchVideo, TikTokApiError>> {
+        let service = self.clone();
+        let start = PageCursor::new(request.cursor).with_search_id(request.search_id.clone());
+
+        paginate(start, move |page| {
+            let service = service.clone();
+            let token = token.clone();
+            let fields = fields.clone();
+            let request = QueryRequest {
+                cursor: page.cursor,
+                search_id: page.search_id,
+                ..request.clone()
+            };
+            async move {
+                service
+                    .query_videos(token.as_auth(), &fields, request)
+                    .await
+            }
+        })
+    }
+
+    /// Streams all videos matching a query between two dates, splitting the range into windows
+    /// TikTok accepts.
+    ///
+    /// TikTok rejects queries spanning more than 30 days, so the range is queried one window at
+    /// a time, each paginated with its own `search_id`. The stream ends after yielding the first
+    /// error.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
+    /// * `fields` - A list of `VideoField` enums for the desired data.
+    /// * `query` - The query conditions applied to every window.
+    /// * `from` - The first day of the range, inclusive.
+    /// * `to` - The last day of the range, inclusive.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of `ResearchVideo` results.
+    pub fn query_videos_range<'a>(
+        &self,
+        token: impl Into<ResearchAuth<'a>>,
+        fields: &[ResearchVideoField],
+        query: ResearchQuery,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> impl Stream<Item = Result<ResearchVideo, TikTokApiError>> {
+        self.query_videos_range_with_progress(token, fields, query, from, to, |_| {})
+    }
+
+    /// Same as `query_videos_range`, calling `progress` when each window starts and completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The client access token, or `ResearchAuth::Cached` to use the token cache.
+    /// * `fields` - A list of `VideoField` enums for the desired data.
+    /// * `query` - The query conditions applied to every window.
+    /// * `from` - The first day of the range, inclusive.
+    /// * `to` - The last day of the range, inclusive.
+    /// * `progress` - Called with the progress of each window.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of `ResearchVideo` results.
+    pub fn query_videos_range_with_progress<'a, F>(
+        &self,
+        token: impl Into<ResearchAuth<'a>>,
+        fields: &[ResearchVideoField],
+        query: ResearchQuery,
+        from: NaiveDate,
+        to: NaiveDate,
+        progress: F,
+    ) -> impl Stream<Item = Result<ResearchVideo, TikTokApiError>>
+    where
+        F: Fn(&DateWindowProgress) + Send + Sync + 'static,
+    {
+        let service = self.clone();
+        let token = OwnedResearchAuth::from(token.into());
+        let fields = fields.to_vec();
+
+        videos_range(query, from, to, progress, move |request| {
+            service
+                .videos_stream(token.clone(), fields.clone(), request)
+                .boxed()
+        })
+    }
+}
+
+/// Fetches the videos of one date window.
+type WindowFetcher = Arc<
+    dyn Fn(QueryRequest) -> BoxStream<'static, Result<ResearchVideo, TikTokApiError>> + Send + Sync,
+>;
+
+/// Streams the videos of every window between `from` and `to`, one window at a time.
+fn videos_range<F, P>(
+    query: ResearchQuery,
+    from: NaiveDate,
+    to: NaiveDate,
+    progress: P,
+    fetch_window: F,
+) -> impl Stream<Item = Result<ResearchVideo, TikTokApiError>>
+where
+    F: Fn(QueryRequest) -> BoxStream<'static, Result<ResearchVideo, TikTokApiError>>
+        + Send
+        + Sync
+        + 'static,
+    P: Fn(&DateWindowProgress) + Send + Sync + 'static,
+{
+    let windows: VecDeque<_> = date_windows(from, to).into_iter().enumerate().collect();
+    let state = RangeState {
+        fetch_window: Arc::new(fetch_window),
+        query,
+        total: windows.len(),
+        windows,
+        current: None,
+        progress: Arc::new(progress),
+    };
+
+    let invalid = (from > to).then(|| {
+        Err(TikTokApiError::InvalidRequest(format!(
+            "start date {} is after end date {}",
+            from, to
+        )))
+    });
+
+    stream::iter(invalid).chain(stream::unfold(state, RangeState::next))
+}
+
+struct CurrentWindow {
+    index: usize,
+    window: DateWindow,
+    videos: usize,
+    stream: BoxStream<'static, Result<ResearchVideo, TikTokApiError>>,
+}
+
+struct RangeState {
+    fetch_window: WindowFetcher,
+    query: ResearchQuery,
+    total: usize,
+    windows: VecDeque<(usize, DateWindow)>,
+    current: Option<CurrentWindow>,
+    progress: Arc<dyn Fn(&DateWindowProgress) + Send + Sync>,
+}
+
+impl RangeState {
+    async fn next(mut self) -> Option<(Result<ResearchVideo, TikTokApiError>, Self)> {
+        loop {
+            if self.current.is_none() {
+                let (index, window) = self.windows.pop_front()?;
+                self.report(index, window, DateWindowStatus::Started);
+                let request = QueryRequest {
+                    query: self.query.clone(),
+                    start_date: window.start_date(),
+                    end_date: window.end_date(),
+                    max_count: Some(100),
+                    cursor: None,
+                    search_id: None,
+                    is_random: None,
+                };
+                let stream = (self.fetch_window)(request);
+                self.current = Some(CurrentWindow {
+                    index,
+                    window,
+                    videos: 0,
+                    stream,
+                });
+            }
+
+            let current = self.current.as_mut()?;
+            match current.stream.next().await {
+                Some(Ok(video)) => {
+                    current.videos += 1;
+                    return Some((Ok(video), self));
+                }
+                Some(Err(e)) => {
+                    self.windows.clear();
+                    self.current = None;
+                    return Some((Err(e), self));
+                }
+                None => {
+                    let (index, window, videos) = (current.index, current.window, current.videos);
+                    self.current = None;
+                    self.report(index, window, DateWindowStatus::Completed { videos });
+                }
+            }
+        }
+    }
+
+    fn report(&self, index: usize, window: DateWindow, status: DateWindowStatus) {
+        (self.progress)(&DateWindowProgress {
+            index,
+            total: self.total,
+            window,
+            status,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::research::ResearchQuery;
+
+    fn page(ids: &[i64], cursor: i64, has_more: bool) -> ResponseTemplate {
+        let videos: Vec<_> = ids.iter().map(|id| json!({ "id": id })).collect();
+        ResponseTemplate::new(200).set_body_json(json!({
+            "data": {"videos": videos, "cursor": cursor, "has_more": has_more, "search_id": "abc"},
+            "error": {"code": "ok", "message": "", "log_id": "log"}
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_query_videos_stream_carries_search_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/research/video/query/"))
+            .and(body_partial_json(json!({"cursor": 2, "search_id": "abc"})))
+            .respond_with(page(&[3], 3, false))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/research/video/query/"))
+            .respond_with(page(&[1, 2], 2, true))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let request = QueryRequest {
+            query: ResearchQuery {
+                and: None,
+                or: None,
+                not: None,
+            },
+            start_date: "20240101".into(),
+            end_date: "20240130".into(),
+            max_count: None,
+            cursor: None,
+            search_id: None,
+            is_random: None,
+        };
+        let videos: Vec<ResearchVideo> = Service::new()
+            .with_base_url(&server.uri())
+            .query_videos_stream("token", &[ResearchVideoField::Id], request)
+            .try_collect()
             .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .unwrap();
+
+        let ids: Vec<_> = videos.iter().map(|v| v.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+    }
 
-        let status = response.status();
-        let body = response
-            .text()
+    #[tokio::test]
+    async fn test_query_videos_range_queries_each_window() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/research/video/query/"))
+            .and(body_partial_json(
+                json!({"start_date": "20240101", "end_date": "20240130"}),
+            ))
+            .respond_with(page(&[1], 1, false))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/research/video/query/"))
+            .and(body_partial_json(
+                json!({"start_date": "20240131", "end_date": "20240205"}),
+            ))
+            .respond_with(page(&[2], 1, false))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let query = ResearchQuery {
+            and: None,
+            or: None,
+            not: None,
+        };
+        let videos: Vec<ResearchVideo> = Service::new()
+            .with_base_url(&server.uri())
+            .query_videos_range_with_progress(
+                "token",
+                &[ResearchVideoField::Id],
+                query,
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 2, 5).unwrap(),
+                move |progress| {
+                    recorded.lock().unwrap().push((
+                        progress.index,
+                        progress.total,
+                        progress.status.clone(),
+                    ))
+                },
+            )
+            .try_collect()
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
+            .unwrap();
+
+        assert_eq!(videos.iter().map(|v| v.id).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                (0, 2, DateWindowStatus::Started),
+                (0, 2, DateWindowStatus::Completed { videos: 1 }),
+                (1, 2, DateWindowStatus::Started),
+                (1, 2, DateWindowStatus::Completed { videos: 1 }),
+            ]
+        );
+    }
+
+    async fn mock_tokens(server: &MockServer, tokens: &[&str]) {
+        for (i, token) in tokens.iter().enumerate() {
+            Mock::given(method("POST"))
+                .and(path("/v2/oauth/token/"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "access_token": token,
+                    "expires_in": 7200,
+                    "token_type": "Bearer"
+                })))
+                .up_to_n_times(1)
+                .with_priority(i as u8 + 1)
+                .mount(server)
+                .await;
+        }
+    }
 
-        let video_comments_response: ResearchVideoCommentsResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
+    fn cached_service(server: &MockServer) -> Service {
+        let credentials =
+            crate::client::Service::with_credentials("key", "secret").with_base_url(&server.uri());
+        Service::new()
+            .with_base_url(&server.uri())
+            .with_token_cache(TokenCache::new(credentials))
+    }
+
+    fn query_request() -> QueryRequest {
+        QueryRequest {
+            query: ResearchQuery::default(),
+            start_date: "20240101".into(),
+            end_date: "20240130".into(),
+            max_count: None,
+            cursor: None,
+            search_id: None,
+            is_random: None,
+        }
+    }
 
-        if status.is_success() && video_comments_response.error.code == "ok" {
-            Ok(video_comments_response.data)
-        } else {
-            Err(TikTokApiError::from(video_comments_response.error))
+    #[tokio::test]
+    async fn test_pages_reuse_cached_token() {
+        let server = MockServer::start().await;
+        mock_tokens(&server, &["first"]).await;
+        for (cursor, has_more) in [(1, true), (2, false)] {
+            Mock::given(method("POST"))
+                .and(path("/v2/research/video/query/"))
+                .and(header("Authorization", "Bearer first"))
+                .respond_with(page(&[cursor], cursor, has_more))
+                .up_to_n_times(1)
+                .with_priority(cursor as u8)
+                .mount(&server)
+                .await;
         }
+
+        let videos: Vec<ResearchVideo> = cached_service(&server)
+            .query_videos_stream(
+                ResearchAuth::Cached,
+                &[ResearchVideoField::Id],
+                query_request(),
+            )
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(videos.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_cached_token_is_replaced_once() {
+        let server = MockServer::start().await;
+        mock_tokens(&server, &["stale", "fresh"]).await;
+        Mock::given(method("POST"))
+            .and(path("/v2/research/video/query/"))
+            .and(header("Authorization", "Bearer stale"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(json!({
+                "data": {},
+                "error": {"code": "access_token_invalid", "message": "", "log_id": "log"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/research/video/query/"))
+            .and(header("Authorization", "Bearer fresh"))
+            .respond_with(page(&[1], 1, false))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let data = cached_service(&server)
+            .query_videos(
+                ResearchAuth::Cached,
+                &[ResearchVideoField::Id],
+                query_request(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(data.videos.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_explicit_token_bypasses_cache() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/oauth/token/"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/research/video/query/"))
+            .and(header("Authorization", "Bearer explicit"))
+            .respond_with(page(&[1], 1, false))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        cached_service(&server)
+            .query_videos("explicit", &[ResearchVideoField::Id], query_request())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cached_auth_without_cache_fails_locally() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(page(&[1], 1, false))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let result = Service::new()
+            .with_base_url(&server.uri())
+            .query_videos(
+                ResearchAuth::Cached,
+                &[ResearchVideoField::Id],
+                query_request(),
+            )
+            .await;
+        assert!(matches!(result, Err(TikTokApiError::InvalidRequest(_))));
     }
 }
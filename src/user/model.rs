@@ -1,7 +1,100 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 
+use crate::auth::TikTokScope;
 use crate::error::ErrorResponse;
 
+/// A field of `UserInfo` that can be requested from `/v2/user/info/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UserField {
+    OpenId,
+    UnionId,
+    AvatarUrl,
+    AvatarUrl100,
+    AvatarLargeUrl,
+    DisplayName,
+    BioDescription,
+    ProfileDeepLink,
+    IsVerified,
+    Username,
+    FollowerCount,
+    FollowingCount,
+    LikesCount,
+    VideoCount,
+}
+
+impl UserField {
+    /// Every user field, in the order TikTok documents them.
+    pub const ALL: [UserField; 14] = [
+        UserField::OpenId,
+        UserField::UnionId,
+        UserField::AvatarUrl,
+        UserField::AvatarUrl100,
+        UserField::AvatarLargeUrl,
+        UserField::DisplayName,
+        UserField::BioDescription,
+        UserField::ProfileDeepLink,
+        UserField::IsVerified,
+        UserField::Username,
+        UserField::FollowerCount,
+        UserField::FollowingCount,
+        UserField::LikesCount,
+        UserField::VideoCount,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserField::OpenId => "open_id",
+            UserField::UnionId => "union_id",
+            UserField::AvatarUrl => "avatar_url",
+            UserField::AvatarUrl100 => "avatar_url_100",
+            UserField::AvatarLargeUrl => "avatar_large_url",
+            UserField::DisplayName => "display_name",
+            UserField::BioDescription => "bio_description",
+            UserField::ProfileDeepLink => "profile_deep_link",
+            UserField::IsVerified => "is_verified",
+            UserField::Username => "username",
+            UserField::FollowerCount => "follower_count",
+            UserField::FollowingCount => "following_count",
+            UserField::LikesCount => "likes_count",
+            UserField::VideoCount => "video_count",
+        }
+    }
+
+    /// Returns the scope the user must have granted for this field to be returned.
+    pub fn scope(&self) -> TikTokScope {
+        match self {
+            UserField::OpenId
+            | UserField::UnionId
+            | UserField::AvatarUrl
+            | UserField::AvatarUrl100
+            | UserField::AvatarLargeUrl
+            | UserField::DisplayName => TikTokScope::UserInfoBasic,
+            UserField::BioDescription
+            | UserField::ProfileDeepLink
+            | UserField::IsVerified
+            | UserField::Username => TikTokScope::UserInfoProfile,
+            UserField::FollowerCount
+            | UserField::FollowingCount
+            | UserField::LikesCount
+            | UserField::VideoCount => TikTokScope::UserInfoStats,
+        }
+    }
+
+    /// Returns every field that can be requested with the `granted` scopes.
+    ///
+    /// # Arguments
+    ///
+    /// * `granted` - The scopes the user granted.
+    pub fn requestable(granted: &HashSet<TikTokScope>) -> Vec<UserField> {
+        UserField::ALL
+            .into_iter()
+            .filter(|field| granted.contains(&field.scope()))
+            .collect()
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 pub struct UserInfo {
     #[serde(default)]
@@ -46,3 +139,21 @@ pub struct UserInfoResponse {
     pub data: UserInfoData,
     pub error: ErrorResponse,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requestable_fields_follow_granted_scopes() {
+        let granted = HashSet::from([TikTokScope::UserInfoBasic, TikTokScope::UserInfoStats]);
+        let fields = UserField::requestable(&granted);
+
+        assert_eq!(fields.len(), 10);
+        assert!(fields.contains(&UserField::AvatarUrl100));
+        assert!(fields.contains(&UserField::FollowerCount));
+        assert!(!fields.contains(&UserField::Username));
+        assert!(UserField::requestable(&HashSet::new()).is_empty());
+        assert_eq!(UserField::AvatarUrl100.as_str(), "avatar_url_100");
+    }
+}
@@ -1,23 +1,41 @@
-use reqwest::Client;
+use crate::api::{decode_envelope, TikTokClient};
+use crate::auth::{AccessToken, TikTokScope};
 
 use crate::error::TikTokApiError;
 
-use super::{UserInfo, UserInfoResponse};
+use super::{UserField, UserInfo, UserInfoResponse};
 
+#[derive(Clone)]
 pub struct Service {
-    base_url: String,
+    client: TikTokClient,
+}
+
+impl Default for Service {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Service {
+    /// The scopes `get_user_info` needs, in addition to the scope of every requested field.
+    pub const USER_INFO_SCOPES: &'static [TikTokScope] = &[TikTokScope::UserInfoBasic];
+
     /// Creates a new instance of the Service with the client key and secret from environment variables.
     ///
     /// # Panics
     ///
     /// Panics if the `TIKTOK_CLIENT_KEY` or `TIKTOK_CLIENT_SECRET` environment variables are not set.
     pub fn new() -> Self {
-        Self {
-            base_url: String::from("https://open.tiktokapis.com"),
-        }
+        Self::from_client(TikTokClient::new())
+    }
+
+    /// Creates a new instance of the Service sharing the given `TikTokClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client whose connection pool and configuration are reused.
+    pub fn from_client(client: TikTokClient) -> Self {
+        Self { client }
     }
 
     /// Sets a custom base URL for the Service.
@@ -26,43 +44,130 @@ impl Service {
     ///
     /// * `base_url` - A string slice that holds the custom base URL.
     pub fn with_base_url(mut self, base_url: &str) -> Self {
-        self.base_url = base_url.into();
+        self.client = self.client.with_base_url(base_url);
         self
     }
 }
 
 impl Service {
+    /// Retrieves information about the authenticated user.
+    ///
+    /// Fields whose scope the user did not grant are not returned; use
+    /// `UserField::requestable` to request only the fields that can be returned. When the
+    /// granted scopes of `access_token` are known, requesting such a field fails with
+    /// `MissingScopes` instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_token` - The OAuth access token for the authenticated user, with its granted
+    ///   scopes if known.
+    /// * `fields` - A vector of UserField enum values to request.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `UserInfo` on success, or a `TikTokApiError` on failure.
     pub async fn get_user_info(
         &self,
-        access_token: &str,
-        fields: Vec<&str>,
+        access_token: impl Into<AccessToken<'_>>,
+        fields: Vec<UserField>,
     ) -> Result<UserInfo, TikTokApiError> {
-        let client = Client::new();
-        let url = format!("{}/v2/user/info/", self.base_url);
+        let access_token = access_token.into();
+        let required = Self::USER_INFO_SCOPES
+            .iter()
+            .copied()
+            .chain(fields.iter().map(UserField::scope))
+            .collect::<Vec<_>>();
+        access_token.require(&required)?;
+
+        let url = self.client.url("/v2/user/info/");
 
-        let fields_str = fields.join(",");
+        let fields_str = fields
+            .iter()
+            .map(|f| f.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
 
-        let response = client
+        let request = self
+            .client
+            .http()
             .get(&url)
             .query(&[("fields", fields_str)])
-            .header("Authorization", format!("Bearer {}", access_token))
-            .send()
+            .header("Authorization", format!("Bearer {}", access_token.as_str()));
+
+        self.client
+            .execute(request, |status, body| {
+                decode_envelope(status, body, |user_info_response: UserInfoResponse| {
+                    user_info_response.data.user
+                })
+            })
             .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
-        let status = response.status();
-        let body = response
-            .text()
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_user_info_requests_typed_fields() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/user/info/"))
+            .and(query_param("fields", "open_id,display_name,follower_count"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {"user": {"open_id": "id", "display_name": "Name", "follower_count": 7}},
+                "error": {"code": "ok", "message": "", "log_id": "log"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let user_info = Service::new()
+            .with_base_url(&server.uri())
+            .get_user_info(
+                "token",
+                vec![
+                    UserField::OpenId,
+                    UserField::DisplayName,
+                    UserField::FollowerCount,
+                ],
+            )
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
+            .unwrap();
+        assert_eq!(user_info.display_name.as_deref(), Some("Name"));
+        assert_eq!(user_info.follower_count, Some(7));
+    }
 
-        let user_info_response: UserInfoResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
+    #[tokio::test]
+    async fn test_get_user_info_fails_fast_without_granted_scope() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/user/info/"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
 
-        if status.is_success() && user_info_response.error.code == "ok" {
-            Ok(user_info_response.data.user)
-        } else {
-            Err(TikTokApiError::from(user_info_response.error))
+        let result = Service::new()
+            .with_base_url(&server.uri())
+            .get_user_info(
+                AccessToken::new("token").with_scope("user.info.basic,video.list"),
+                vec![
+                    UserField::DisplayName,
+                    UserField::FollowerCount,
+                    UserField::LikesCount,
+                ],
+            )
+            .await;
+        match result {
+            Err(TikTokApiError::MissingScopes(missing)) => {
+                assert_eq!(missing, vec![TikTokScope::UserInfoStats])
+            }
+            other => panic!("expected MissingScopes, got {:?}", other),
         }
     }
 }
@@ -1,4 +1,6 @@
 use crate::error::ErrorResponse;
+use crate::pagination::CursorPage;
+use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy)]
@@ -117,9 +119,37 @@ pub struct UserVideoListPostResponseData {
     pub has_more: bool,
 }
 
+impl CursorPage for UserVideoListPostResponseData {
+    type Item = Video;
+
+    fn cursor(&self) -> i64 {
+        self.cursor
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    fn into_items(self) -> Vec<Video> {
+        self.videos
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ListVideoResponse {
     #[serde(default)]
     pub data: UserVideoListPostResponseData,
     pub error: ErrorResponse,
 }
+
+/// Options for `Service::list_videos_stream_with_options`.
+#[derive(Clone, Debug, Default, Builder)]
+#[builder(setter(into, strip_option), default)]
+pub struct ListVideosOptions {
+    /// Number of videos requested per page (default is 10, maximum is 20).
+    pub max_count: Option<i32>,
+    /// Maximum number of videos yielded by the stream.
+    pub limit: Option<usize>,
+    /// Cursor to start from, e.g. to resume a previous listing.
+    pub cursor: Option<i64>,
+}
@@ -1,22 +1,46 @@
 use crate::error::TikTokApiError;
+use crate::pagination::{paginate, PageCursor};
 
-use reqwest::Client;
+use crate::api::{decode_envelope, TikTokClient};
+use crate::auth::{AccessToken, OwnedAccessToken, TikTokScope};
+
+use futures::{Stream, StreamExt};
 
 use super::{
-    ListVideoRequest, ListVideoResponse, QueryVideoRequest, QueryVideoResponse,
+    ListVideoRequest, ListVideoResponse, ListVideosOptions, QueryVideoRequest, QueryVideoResponse,
     UserVideoListPostResponseData, Video, VideoField, VideoFilters,
 };
 
+#[derive(Clone)]
 pub struct Service {
-    base_url: String,
+    client: TikTokClient,
+}
+
+impl Default for Service {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Service {
+    /// The scopes `query_videos` needs.
+    pub const QUERY_VIDEOS_SCOPES: &'static [TikTokScope] = &[TikTokScope::VideoList];
+
+    /// The scopes `list_videos` and the video list streams need.
+    pub const LIST_VIDEOS_SCOPES: &'static [TikTokScope] = &[TikTokScope::VideoList];
+
     /// Creates a new instance of the Service.
     pub fn new() -> Self {
-        Self {
-            base_url: String::from("https://open.tiktokapis.com"),
-        }
+        Self::from_client(TikTokClient::new())
+    }
+
+    /// Creates a new instance of the Service sharing the given `TikTokClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client whose connection pool and configuration are reused.
+    pub fn from_client(client: TikTokClient) -> Self {
+        Self { client }
     }
 
     /// Sets a custom base URL for the Service.
@@ -25,7 +49,7 @@ impl Service {
     ///
     /// * `base_url` - A string slice that holds the custom base URL.
     pub fn with_base_url(mut self, base_url: &str) -> Self {
-        self.base_url = base_url.into();
+        self.client = self.client.with_base_url(base_url);
         self
     }
 
@@ -42,12 +66,14 @@ impl Service {
     /// Returns a Result containing a Vec<Video> if successful, or a TikTokApiError if an error occurs.
     pub async fn query_videos(
         &self,
-        access_token: &str,
+        access_token: impl Into<AccessToken<'_>>,
         video_ids: Vec<String>,
         fields: Vec<VideoField>,
     ) -> Result<Vec<Video>, TikTokApiError> {
-        let client = Client::new();
-        let url = format!("{}/v2/video/query/", self.base_url);
+        let access_token = access_token.into();
+        access_token.require(Self::QUERY_VIDEOS_SCOPES)?;
+
+        let url = self.client.url("/v2/video/query/");
 
         let fields_str = fields
             .iter()
@@ -58,30 +84,22 @@ impl Service {
             filters: VideoFilters { video_ids },
         };
 
-        let response = client
+        let request = self
+            .client
+            .http()
             .post(&url)
             .query(&[("fields", fields_str)])
-            .header("Authorization", format!("Bearer {}", access_token))
+            .header("Authorization", format!("Bearer {}", access_token.as_str()))
             .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .json(&request_body);
 
-        let status = response.status();
-        let body = response
-            .text()
+        self.client
+            .execute(request, |status, body| {
+                decode_envelope(status, body, |query_video_response: QueryVideoResponse| {
+                    query_video_response.data.videos
+                })
+            })
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
-
-        let query_video_response: QueryVideoResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
-
-        if status.is_success() && query_video_response.error.code == "ok" {
-            Ok(query_video_response.data.videos)
-        } else {
-            Err(TikTokApiError::from(query_video_response.error))
-        }
     }
 
     /// List videos for the authenticated user.
@@ -98,13 +116,15 @@ impl Service {
     /// Returns a Result containing UserVideoListPostResponseData if successful, or a TikTokApiError if an error occurs.
     pub async fn list_videos(
         &self,
-        access_token: &str,
+        access_token: impl Into<AccessToken<'_>>,
         cursor: Option<i64>,
         max_count: Option<i32>,
         fields: Vec<VideoField>,
     ) -> Result<UserVideoListPostResponseData, TikTokApiError> {
-        let client = Client::new();
-        let url = format!("{}/v2/video/list/", self.base_url);
+        let access_token = access_token.into();
+        access_token.require(Self::LIST_VIDEOS_SCOPES)?;
+
+        let url = self.client.url("/v2/video/list/");
 
         let fields_str = fields
             .iter()
@@ -114,29 +134,179 @@ impl Service {
 
         let request_body = ListVideoRequest { cursor, max_count };
 
-        let response = client
+        let request = self
+            .client
+            .http()
             .post(&url)
             .query(&[("fields", fields_str)])
-            .header("Authorization", format!("Bearer {}", access_token))
+            .header("Authorization", format!("Bearer {}", access_token.as_str()))
             .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
+            .json(&request_body);
+
+        self.client
+            .execute(request, |status, body| {
+                decode_envelope(status, body, |list_video_response: ListVideoResponse| {
+                    list_video_response.data
+                })
+            })
+            .await
+    }
+
+    /// Lists all videos of the authenticated user as a stream, following the pagination cursor
+    /// until TikTok reports there are no more videos.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_token` - The OAuth access token for the authenticated user.
+    /// * `fields` - A vector of VideoField enum values to request.
+    ///
+    /// # Returns
+    ///
+    /// Returns a Stream of videos. The stream ends after yielding the first error.
+    pub fn list_videos_stream<'a>(
+        &self,
+        access_token: impl Into<AccessToken<'a>>,
+        fields: Vec<VideoField>,
+    ) -> impl Stream<Item = Result<Video, TikTokApiError>> {
+        self.list_videos_stream_with_options(access_token, fields, ListVideosOptions::default())
+    }
+
+    /// Lists videos of the authenticated user as a stream with custom page size, item limit and
+    /// starting cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_token` - The OAuth access token for the authenticated user.
+    /// * `fields` - A vector of VideoField enum values to request.
+    /// * `options` - The pagination options.
+    ///
+    /// # Returns
+    ///
+    /// Returns a Stream of videos. The stream ends after yielding the first error.
+    pub fn list_videos_stream_with_options<'a>(
+        &self,
+        access_token: impl Into<AccessToken<'a>>,
+        fields: Vec<VideoField>,
+        options: ListVideosOptions,
+    ) -> impl Stream<Item = Result<Video, TikTokApiError>> {
+        let service = self.clone();
+        let access_token = OwnedAccessToken::from(access_token.into());
+        let max_count = options.max_count;
+
+        paginate(PageCursor::new(options.cursor), move |page| {
+            let service = service.clone();
+            let access_token = access_token.clone();
+            let fields = fields.clone();
+            async move {
+                service
+                    .list_videos(access_token.as_token(), page.cursor, max_count, fields)
+                    .await
+            }
+        })
+        .take(options.limit.unwrap_or(usize::MAX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::videos::ListVideosOptionsBuilder;
+
+    fn page(ids: &[&str], cursor: i64, has_more: bool) -> ResponseTemplate {
+        let videos: Vec<_> = ids.iter().map(|id| json!({ "id": id })).collect();
+        ResponseTemplate::new(200).set_body_json(json!({
+            "data": {"videos": videos, "cursor": cursor, "has_more": has_more},
+            "error": {"code": "ok", "message": "", "log_id": "log"}
+        }))
+    }
+
+    async fn mock_pages() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/video/list/"))
+            .and(body_json(json!({})))
+            .respond_with(page(&["1", "2"], 100, true))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v2/video/list/"))
+            .and(body_json(json!({ "cursor": 100 })))
+            .respond_with(page(&["3"], 200, false))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_list_videos_stream_follows_cursor() {
+        let server = mock_pages().await;
+        let service = Service::new().with_base_url(&server.uri());
+
+        let videos: Vec<Video> = service
+            .list_videos_stream("token", vec![VideoField::Id])
+            .try_collect()
             .await
-            .map_err(|e| TikTokApiError::RequestFailed(e.to_string()))?;
+            .unwrap();
+        let ids: Vec<_> = videos.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, ["1", "2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_videos_stream_honors_limit_and_cursor() {
+        let server = mock_pages().await;
+        let service = Service::new().with_base_url(&server.uri());
 
-        let status = response.status();
-        let body = response
-            .text()
+        let options = ListVideosOptionsBuilder::default()
+            .limit(1usize)
+            .build()
+            .unwrap();
+        let videos: Vec<Video> = service
+            .list_videos_stream_with_options("token", vec![VideoField::Id], options)
+            .try_collect()
             .await
-            .map_err(|e| TikTokApiError::ResponseReadFailed(e.to_string()))?;
+            .unwrap();
+        assert_eq!(videos.len(), 1);
 
-        let list_video_response: ListVideoResponse =
-            serde_json::from_str(&body).map_err(|e| TikTokApiError::ParseFailed(e.to_string()))?;
+        let options = ListVideosOptionsBuilder::default()
+            .cursor(100)
+            .build()
+            .unwrap();
+        let videos: Vec<Video> = service
+            .list_videos_stream_with_options("token", vec![VideoField::Id], options)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(videos[0].id, "3");
+    }
+
+    #[tokio::test]
+    async fn test_shared_service_checks_scopes_of_each_token() {
+        let server = mock_pages().await;
+        let service = Service::new().with_base_url(&server.uri());
+        let granted = AccessToken::new("token").with_scope("user.info.basic,video.list");
+        let not_granted = AccessToken::new("other").with_scope("user.info.basic");
+
+        let videos: Vec<Video> = service
+            .list_videos_stream(granted, vec![VideoField::Id])
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(videos.len(), 3);
 
-        if status.is_success() && list_video_response.error.code == "ok" {
-            Ok(list_video_response.data)
-        } else {
-            Err(TikTokApiError::from(list_video_response.error))
+        let result = service
+            .list_videos(not_granted, None, None, vec![VideoField::Id])
+            .await;
+        match result {
+            Err(TikTokApiError::MissingScopes(missing)) => {
+                assert_eq!(missing, vec![TikTokScope::VideoList])
+            }
+            other => panic!("expected MissingScopes, got {:?}", other),
         }
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
     }
 }